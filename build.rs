@@ -0,0 +1,19 @@
+use std::process::Command;
+
+// 编译时记录git提交哈希,用于/version接口(没有git仓库时可以通过环境变量GIT_HASH指定)
+fn main() {
+    let git_hash = std::env::var("GIT_HASH").ok().filter(|x| !x.is_empty()).unwrap_or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()
+            .filter(|x| x.status.success())
+            .map(|x| String::from_utf8_lossy(&x.stdout).trim().to_string())
+            .unwrap_or("unknown".to_string())
+    });
+
+    println!("cargo:rustc-env=GIT_HASH={}", git_hash);
+    println!("cargo:rerun-if-env-changed=GIT_HASH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
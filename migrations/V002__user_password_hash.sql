@@ -0,0 +1,3 @@
+-- 密码改为argon2id哈希存储, 明文密码会在下次登录成功后自动升级
+alter table sys_user
+    modify password varchar(255) not null comment '密码(argon2id哈希)';
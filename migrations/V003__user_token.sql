@@ -0,0 +1,19 @@
+-- 刷新令牌(轮换使用,只保存哈希)
+create table sys_user_token
+(
+    id          bigint auto_increment comment '主键'
+        primary key,
+    user_id     bigint                             not null comment '用户ID',
+    token_hash  char(64)                           not null comment '刷新令牌哈希(sha256)',
+    family_id   char(64)                           not null comment '令牌族(同一次登录轮换产生的令牌共用)',
+    replaced_by bigint                             null comment '轮换后的新令牌ID',
+    status      tinyint  default 1                 not null comment '状态(1:有效,2:已轮换,0:已撤销)',
+    expire_time datetime                           not null comment '过期时间',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    constraint AK_token_hash
+        unique (token_hash)
+) comment = '用户刷新令牌';
+
+create index idx_user_token_user_id on sys_user_token (user_id);
+create index idx_user_token_family_id on sys_user_token (family_id);
@@ -0,0 +1,15 @@
+-- 双因素认证(TOTP)
+create table sys_user_mfa
+(
+    id             bigint auto_increment comment '主键'
+        primary key,
+    user_id        bigint                             not null comment '用户ID',
+    secret         varchar(64)                        not null comment 'TOTP密钥(base32)',
+    status         tinyint  default 0                 not null comment '状态(0:待确认,1:已开启)',
+    recovery_codes varchar(1024) default ''           not null comment '恢复码哈希(sha256,逗号分隔,使用后删除)',
+    last_used_step bigint   default 0                 not null comment '最后一次使用的时间步(防止验证码重复使用)',
+    create_time    datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time    datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    constraint AK_user_id
+        unique (user_id)
+) comment = '用户双因素认证';
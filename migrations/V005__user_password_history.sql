@@ -0,0 +1,11 @@
+-- 历史密码(修改密码时不能和最近几次的密码相同)
+create table sys_user_password_history
+(
+    id          bigint auto_increment comment '主键'
+        primary key,
+    user_id     bigint                             not null comment '用户ID',
+    password    varchar(255)                       not null comment '密码哈希',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间'
+) comment = '用户历史密码';
+
+create index idx_user_id on sys_user_password_history (user_id);
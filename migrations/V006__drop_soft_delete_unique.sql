@@ -0,0 +1,8 @@
+-- 逻辑删除后手机号和角色名称可以重新使用,去掉包含已删除数据的唯一约束
+alter table sys_user
+    drop index AK_phone;
+create index idx_mobile
+    on sys_user (mobile);
+
+alter table sys_role
+    drop index role_name;
@@ -0,0 +1,20 @@
+-- 退出登录、解锁用户、双因素认证、角色数据权限、在线用户和回收站的菜单权限
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '退出登录', 3, 1, 11, id, '', '/api/system/user/logout', '', '退出登录' FROM sys_menu WHERE menu_name = '用户管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '解锁用户', 3, 1, 12, id, '', '/api/system/user/unlockUser', '', '解锁用户' FROM sys_menu WHERE menu_name = '用户管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '获取双因素认证密钥', 3, 1, 13, id, '', '/api/system/user/2fa/setup', '', '获取双因素认证密钥' FROM sys_menu WHERE menu_name = '用户管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '开启双因素认证', 3, 1, 14, id, '', '/api/system/user/2fa/enable', '', '开启双因素认证' FROM sys_menu WHERE menu_name = '用户管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '重置双因素认证', 3, 1, 15, id, '', '/api/system/user/2fa/reset', '', '重置双因素认证' FROM sys_menu WHERE menu_name = '用户管理';
+
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '查询角色部门列表', 3, 1, 9, id, '', '/api/system/role/queryRoleDept', '', '查询角色部门列表' FROM sys_menu WHERE menu_name = '角色管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '更新角色部门信息', 3, 1, 10, id, '', '/api/system/role/updateRoleDept', '', '更新角色部门信息' FROM sys_menu WHERE menu_name = '角色管理';
+
+-- 配置在线用户权限
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '在线用户', 2, 1, 11, id, '/log/online', '', 'TeamOutlined', '在线用户管理' FROM sys_menu WHERE menu_name = '日志管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '查询在线用户列表', 3, 1, 1, id, '', '/api/system/online/queryOnlineList', '', '查询在线用户列表' FROM sys_menu WHERE menu_name = '在线用户';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '强制退出', 3, 1, 2, id, '', '/api/system/online/forceLogout', '', '强制退出' FROM sys_menu WHERE menu_name = '在线用户';
+
+-- 配置回收站权限
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '回收站', 2, 1, 9, id, '/system/recycle', '', 'RestOutlined', '回收站管理' FROM sys_menu WHERE menu_name = '权限管理';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '查询回收站列表', 3, 1, 1, id, '', '/api/system/recycle/queryList', '', '查询回收站列表' FROM sys_menu WHERE menu_name = '回收站';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '恢复回收站数据', 3, 1, 2, id, '', '/api/system/recycle/restore', '', '恢复回收站数据' FROM sys_menu WHERE menu_name = '回收站';
+INSERT INTO sys_menu (menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) SELECT '彻底删除回收站数据', 3, 1, 3, id, '', '/api/system/recycle/purge', '', '彻底删除回收站数据' FROM sys_menu WHERE menu_name = '回收站';
@@ -0,0 +1,4 @@
+-- 旧账号没有密码修改时间,按创建时间计算会在开启密码有效期后立即过期,从升级时开始计算
+update sys_user
+set pwd_update_date = now()
+where pwd_update_date is null;
@@ -0,0 +1,11 @@
+-- 只对未删除的数据做唯一约束: 生成列在已删除(del_flag = 0)时为null,唯一索引不限制null
+-- 执行前需要确认未删除的用户手机号码和角色名称没有重复
+alter table sys_user
+    add mobile_key char(11) as (if(del_flag = 1 and mobile <> '', mobile, null)) virtual comment '未删除用户的手机号码(唯一约束)';
+create unique index uk_mobile_key
+    on sys_user (mobile_key);
+
+alter table sys_role
+    add role_name_key varchar(50) as (if(del_flag = 1, role_name, null)) virtual comment '未删除角色的名称(唯一约束)';
+create unique index uk_role_name_key
+    on sys_role (role_name_key);
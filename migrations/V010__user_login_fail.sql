@@ -0,0 +1,17 @@
+-- 登录失败和锁定记录(保存在数据库中,重启服务后仍然有效,解锁接口和命令行都可以清除)
+create table sys_user_login_fail
+(
+    id              bigint auto_increment comment '主键'
+        primary key,
+    user_id         bigint                                 not null comment '用户ID',
+    ip              varchar(128) default ''                not null comment '登录IP(按账号锁定时为空)',
+    fail_count      int          default 0                 not null comment '时间窗口内的失败次数',
+    first_fail_time datetime                               null comment '时间窗口内第一次失败的时间',
+    lock_until      datetime                               null comment '锁定截止时间',
+    lock_reason     varchar(255) default ''                not null comment '锁定原因',
+    update_time     datetime     default CURRENT_TIMESTAMP not null comment '修改时间',
+    constraint AK_user_ip
+        unique (user_id, ip)
+) comment = '用户登录失败记录';
+
+create index idx_login_fail_update_time on sys_user_login_fail (update_time);
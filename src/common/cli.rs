@@ -0,0 +1,310 @@
+use crate::common::config::{AppConfig, APP_CONFIG};
+use crate::common::error::{AppError, AppResult};
+use crate::handler::system::sys_user_handler::{add_password_history, check_new_password, save_password};
+use crate::model::system::sys_user_mfa_model::UserMfa;
+use crate::model::system::sys_user_model::User;
+use crate::model::system::sys_user_role_model::UserRole;
+use crate::model::system::sys_user_token_model::revoke_user_token;
+use crate::utils::jwt_util::JwtConfig;
+use crate::utils::login_lock_util::unlock_user;
+use crate::utils::migration_util::migrate;
+use crate::utils::password_util::hash_password;
+use crate::utils::tx_util::in_transaction;
+use crate::vo::system::sys_user_vo::UserReq;
+use crate::RB;
+use rbatis::rbatis_codegen::ops::AsProxy;
+use rbatis::rbdc::datetime::DateTime;
+use rbs::value;
+use std::collections::HashMap;
+
+// 超级管理员角色ID(预设数据)
+const ADMIN_ROLE_ID: i64 = 1;
+
+pub const USAGE: &str = "用法: ntex-admin [命令]
+
+命令:
+  serve                              启动HTTP服务(默认)
+  migrate                            执行数据库迁移
+  check-config                       校验配置文件
+  create-admin <账号> [--mobile <手机号码>] [--email <邮箱>] [--nick-name <昵称>]
+                                     创建超级管理员(密码在终端输入)
+  reset-password <账号/手机号码/邮箱>  重置用户密码(密码在终端输入)
+  unlock <账号/手机号码/邮箱> [--reset-2fa]
+                                     启用被禁用的用户并清除登录失败锁定,--reset-2fa同时关闭双因素认证
+  help                               显示帮助";
+
+/*
+ *命令行子命令
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Serve,
+    Migrate,
+    CheckConfig,
+    CreateAdmin { user_name: String, mobile: String, email: String, nick_name: String },
+    ResetPassword { account: String },
+    Unlock { account: String, reset_two_factor: bool },
+    Help,
+}
+
+impl Command {
+    /*
+     *解析命令行参数(不包括程序名称),没有参数时启动HTTP服务
+     *author：刘飞华
+     *date：2025/01/10 09:21:35
+     */
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+        let mut args = args.into_iter();
+        let name = match args.next() {
+            None => return Ok(Command::Serve),
+            Some(x) => x,
+        };
+        let rest = args.collect::<Vec<String>>();
+
+        let command = match name.as_str() {
+            "serve" => Command::Serve,
+            "migrate" => Command::Migrate,
+            "check-config" => Command::CheckConfig,
+            "help" | "-h" | "--help" => Command::Help,
+            "create-admin" => {
+                let (user_name, mut options) = parse_args(&rest, &["--mobile", "--email", "--nick-name"], &[])?;
+                Command::CreateAdmin {
+                    nick_name: options.remove("--nick-name").unwrap_or_else(|| user_name.clone()),
+                    mobile: options.remove("--mobile").unwrap_or_default(),
+                    email: options.remove("--email").unwrap_or_default(),
+                    user_name,
+                }
+            }
+            "reset-password" => {
+                let (account, _) = parse_args(&rest, &[], &[])?;
+                Command::ResetPassword { account }
+            }
+            "unlock" => {
+                let (account, options) = parse_args(&rest, &[], &["--reset-2fa"])?;
+                Command::Unlock {
+                    account,
+                    reset_two_factor: options.contains_key("--reset-2fa"),
+                }
+            }
+            other => return Err(format!("未知命令: {}", other)),
+        };
+
+        if matches!(command, Command::Serve | Command::Migrate | Command::CheckConfig | Command::Help) && !rest.is_empty() {
+            return Err(format!("命令 {} 不需要参数", name));
+        }
+        Ok(command)
+    }
+}
+
+// 解析一个必填的位置参数和可选参数(options需要值,flags不需要值)
+fn parse_args(args: &[String], options: &[&str], flags: &[&str]) -> Result<(String, HashMap<String, String>), String> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut values = HashMap::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if options.contains(&arg.as_str()) {
+            let value = iter.next().ok_or(format!("参数 {} 缺少值", arg))?;
+            values.insert(arg.clone(), value.clone());
+        } else if flags.contains(&arg.as_str()) {
+            values.insert(arg.clone(), "".to_string());
+        } else if arg.starts_with("--") {
+            return Err(format!("未知参数: {}", arg));
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    match positional.len() {
+        1 => Ok((positional.remove(0), values)),
+        0 => Err("缺少用户账号".to_string()),
+        _ => Err(format!("多余的参数: {}", positional[1..].join(" "))),
+    }
+}
+
+/*
+ *校验配置文件、jwt密钥和日志配置(不连接数据库)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn check_config() -> AppResult<()> {
+    let config = AppConfig::load()?;
+    JwtConfig::from_config(&config.jwt)?;
+    log4rs::config::load_config_file(&config.log.config_file, Default::default()).map_err(|err| AppError::ConfigError(format!("log.config_file 格式错误: {}", err)))?;
+
+    println!("配置正确: {}:{}, jwt: {}", config.server.host, config.server.port, config.jwt.algorithm);
+    Ok(())
+}
+
+/*
+ *执行需要数据库的子命令(数据库连接池已初始化)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn run(command: Command) -> AppResult<()> {
+    match command {
+        Command::Migrate => {
+            let count = migrate(&RB).await?;
+            println!("执行了{}个数据库迁移", count);
+            Ok(())
+        }
+        Command::CreateAdmin { user_name, mobile, email, nick_name } => create_admin(user_name, mobile, email, nick_name).await,
+        Command::ResetPassword { account } => reset_password(&account).await,
+        Command::Unlock { account, reset_two_factor } => unlock(&account, reset_two_factor).await,
+        Command::Serve | Command::CheckConfig | Command::Help => Ok(()),
+    }
+}
+
+async fn create_admin(user_name: String, mobile: String, email: String, nick_name: String) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    if User::select_by_user_name(rb, &user_name).await?.is_some() {
+        return Err(AppError::BusinessError("登录账号已存在"));
+    }
+
+    if !mobile.is_empty() && User::select_by_mobile(rb, &mobile).await?.is_some() {
+        return Err(AppError::BusinessError("手机号码已存在"));
+    }
+
+    if !email.is_empty() && User::select_by_email(rb, &email).await?.is_some() {
+        return Err(AppError::BusinessError("邮箱账号已存在"));
+    }
+
+    let password = read_new_password()?;
+    check_new_password(&user_name, &password, None).await?;
+
+    let req = UserReq {
+        id: None,
+        mobile,
+        user_name: user_name.clone(),
+        nick_name,
+        password: hash_password(&password)?,
+        email,
+        avatar: None,
+        status: 1,
+        dept_id: 1,
+        remark: Some("命令行创建的超级管理员".to_string()),
+        post_ids: vec![],
+    };
+    let mut user = User::from(req);
+    user.pwd_update_date = Some(DateTime::now());
+
+    let id = in_transaction(|tx| {
+        Box::pin(async move {
+            let id = User::insert(tx, &user).await?.last_insert_id.i64();
+            add_password_history(tx, id, &user.password).await?;
+
+            let user_role = UserRole {
+                id: None,
+                user_id: id,
+                role_id: ADMIN_ROLE_ID,
+                create_time: Some(DateTime::now()),
+            };
+            UserRole::insert(tx, &user_role).await?;
+            Ok(id)
+        })
+    })
+    .await?;
+
+    println!("已创建超级管理员: {}(id: {})", user_name, id);
+    Ok(())
+}
+
+async fn reset_password(account: &str) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let mut user = match User::select_by_account(rb, account).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(x) => x,
+    };
+
+    let password = read_new_password()?;
+    check_new_password(&user.user_name, &password, Some(&user)).await?;
+    save_password(&mut user, &password).await?;
+
+    // 刷新令牌保存在数据库中可以直接撤销,访问令牌在有效期(jwt.expire_secs)后失效
+    let user_id = user.id.unwrap_or_default();
+    revoke_user_token(rb, &user_id).await?;
+
+    println!("已重置用户 {} 的密码,刷新令牌已撤销", user.user_name);
+    println!(
+        "警告: 已签发的访问令牌不能在命令行撤销,仍然有效直到过期(最长{}秒),需要立即失效请通过 /online/forceLogout 接口强制下线",
+        APP_CONFIG.jwt.expire_secs
+    );
+    Ok(())
+}
+
+async fn unlock(account: &str, reset_two_factor: bool) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let user = match User::select_by_account(rb, account).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(x) => x,
+    };
+    let user_id = user.id.unwrap_or_default();
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            tx.exec("update sys_user set status = 1 where id = ?", vec![value!(user_id)]).await?;
+            if reset_two_factor {
+                UserMfa::delete_by_map(tx, value! {"user_id": user_id}).await?;
+            }
+            Ok(())
+        })
+    })
+    .await?;
+
+    unlock_user(&[user_id]).await?;
+
+    println!("已启用并解锁用户 {}{}", user.user_name, if reset_two_factor { ",双因素认证已关闭" } else { "" });
+    Ok(())
+}
+
+fn read_new_password() -> AppResult<String> {
+    let read = |prompt: &str| rpassword::prompt_password(prompt).map_err(|err| AppError::ConfigError(format!("读取密码失败: {}", err)));
+
+    let password = read("请输入新密码: ")?;
+    if password != read("请再次输入新密码: ")? {
+        return Err(AppError::BusinessError("两次输入的密码不一致"));
+    }
+    Ok(password)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::cli::Command;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        Command::parse(args.split_whitespace().map(|x| x.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(""), Ok(Command::Serve));
+        assert_eq!(parse("migrate"), Ok(Command::Migrate));
+        assert_eq!(
+            parse("create-admin root --email root@qq.com"),
+            Ok(Command::CreateAdmin {
+                user_name: "root".to_string(),
+                mobile: "".to_string(),
+                email: "root@qq.com".to_string(),
+                nick_name: "root".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("unlock admin --reset-2fa"),
+            Ok(Command::Unlock {
+                account: "admin".to_string(),
+                reset_two_factor: true,
+            })
+        );
+        assert!(parse("reset-password").is_err());
+        assert!(parse("reset-password admin test").is_err());
+        assert!(parse("create-admin root --mobile").is_err());
+        assert!(parse("unlock admin --force").is_err());
+        assert!(parse("migrate now").is_err());
+        assert!(parse("start").is_err());
+    }
+}
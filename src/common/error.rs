@@ -20,6 +20,15 @@ pub enum AppError {
 
     #[error("业务异常: {0}")]
     BusinessError(&'static str),
+
+    #[error("配置错误: {0}")]
+    ConfigError(String),
+
+    #[error("密码不符合安全策略: {0}")]
+    PasswordPolicyError(String),
+
+    #[error("数据库迁移失败: {0}")]
+    MigrationError(String),
 }
 
 pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,54 @@
+use crate::vo::other::health_vo::{HealthResp, VersionResp};
+use crate::RB;
+use ntex::http::Response;
+use ntex::time::{timeout, Millis};
+use ntex::web;
+
+// 就绪检查时数据库的超时时间
+const READY_TIMEOUT: Millis = Millis(3000);
+
+/*
+ *存活检查(进程能够处理请求即可)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::get("/health/live")]
+pub async fn health_live() -> Response {
+    web::HttpResponse::Ok().json(&HealthResp { status: "UP", database: None })
+}
+
+/*
+ *就绪检查(数据库连接池可用才接收流量,否则返回503)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::get("/health/ready")]
+pub async fn health_ready() -> Response {
+    match timeout(READY_TIMEOUT, RB.exec("select 1", vec![])).await {
+        Ok(Ok(_)) => web::HttpResponse::Ok().json(&HealthResp { status: "UP", database: Some("UP") }),
+        result => {
+            match result {
+                Ok(Err(err)) => log::error!("health ready ping database error: {}", err),
+                _ => log::error!("health ready ping database timeout"),
+            }
+            web::HttpResponse::ServiceUnavailable().json(&HealthResp {
+                status: "DOWN",
+                database: Some("DOWN"),
+            })
+        }
+    }
+}
+
+/*
+ *版本信息
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::get("/version")]
+pub async fn version() -> Response {
+    web::HttpResponse::Ok().json(&VersionResp {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        git_hash: env!("GIT_HASH"),
+    })
+}
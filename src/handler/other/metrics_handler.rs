@@ -0,0 +1,37 @@
+use crate::common::config::APP_CONFIG;
+use crate::utils::ip_util::{client_ip, IpNet};
+use crate::utils::metrics_util::{gather, set_db_pool_state};
+use crate::RB;
+use ntex::http::Response;
+use ntex::web;
+use std::net::IpAddr;
+
+lazy_static! {
+    // 允许访问/metrics的地址(server.metrics_allowed,启动时已校验格式)
+    static ref METRICS_ALLOWED: Vec<IpNet> = APP_CONFIG.server.metrics_allowed.iter().filter_map(|x| IpNet::parse(x)).collect();
+}
+
+/*
+ *Prometheus指标(文本格式),不需要登录,只允许server.metrics_allowed中的地址访问
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::get("/metrics")]
+pub async fn metrics(http_req: web::HttpRequest) -> Response {
+    let ipaddr = client_ip(http_req.peer_addr(), http_req.headers());
+    let allowed = ipaddr.parse::<IpAddr>().is_ok_and(|ip| METRICS_ALLOWED.iter().any(|x| x.contains(&ip)));
+    if !allowed {
+        log::warn!("metrics access denied: {}", ipaddr);
+        return web::HttpResponse::Forbidden().finish();
+    }
+
+    match RB.get_pool() {
+        Ok(pool) => match serde_json::to_value(pool.state().await) {
+            Ok(state) => set_db_pool_state(&state),
+            Err(err) => log::error!("convert db pool state error: {}", err),
+        },
+        Err(err) => log::error!("get db pool error: {}", err),
+    }
+
+    web::HttpResponse::Ok().content_type("text/plain; version=0.0.4; charset=utf-8").body(gather())
+}
@@ -1 +1,2 @@
-
+pub mod health_handler;
+pub mod metrics_handler;
@@ -1,10 +1,13 @@
+pub mod sys_captcha_handler;
 pub mod sys_dept_handler;
 pub mod sys_dict_data_handler;
 pub mod sys_dict_type_handler;
 pub mod sys_login_log_handler;
 pub mod sys_menu_handler;
 pub mod sys_notice_handler;
+pub mod sys_online_handler;
 pub mod sys_operate_log_handler;
 pub mod sys_post_handler;
+pub mod sys_recycle_handler;
 pub mod sys_role_handler;
 pub mod sys_user_handler;
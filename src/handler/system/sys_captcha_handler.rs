@@ -0,0 +1,25 @@
+use crate::common::error::AppResult;
+use crate::common::result::ok_result_data;
+use crate::utils::captcha_util::create_captcha;
+use crate::utils::ip_util::client_ip;
+use crate::vo::system::sys_captcha_vo::CaptchaResp;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ntex::http::Response;
+use ntex::web;
+
+/*
+ *获取图片验证码
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/captcha/image")]
+pub async fn captcha_image(http_req: web::HttpRequest) -> AppResult<Response> {
+    let ipaddr = client_ip(http_req.peer_addr(), http_req.headers());
+    let (captcha_key, image) = create_captcha(&ipaddr)?;
+
+    ok_result_data(CaptchaResp {
+        captcha_key,
+        image: format!("data:image/png;base64,{}", STANDARD.encode(image)),
+    })
+}
@@ -1,6 +1,8 @@
 use crate::common::error::{AppError, AppResult};
 use crate::common::result::{ok_result, ok_result_data};
-use crate::model::system::sys_dept_model::{check_dept_exist_user, select_children_dept_by_id, select_dept_count, select_normal_children_dept_by_id, Dept};
+use crate::model::system::sys_dept_model::{check_dept_exist_user, select_children_dept_by_id, select_dept_count, select_normal_children_dept_by_id, soft_delete_dept_by_ids, Dept};
+use crate::utils::data_scope_util::query_data_scope;
+use crate::utils::tx_util::in_transaction;
 use crate::vo::system::sys_dept_vo::*;
 use crate::RB;
 use log::info;
@@ -61,7 +63,7 @@ pub async fn delete_sys_dept(item: Json<DeleteDeptReq>) -> AppResult<Response> {
         return Err(AppError::BusinessError("部门存在用户,不允许删除"));
     }
 
-    Dept::delete_by_map(rb, value! {"id": &item.id}).await?;
+    soft_delete_dept_by_ids(rb, &[item.id]).await?;
     ok_result()
 }
 
@@ -104,29 +106,44 @@ pub async fn update_sys_dept(item: Json<DeptReq>) -> AppResult<Response> {
         return Err(AppError::BusinessError("该部门包含未停用的子部门"));
     }
 
-    for mut x in select_children_dept_by_id(rb, &id.unwrap_or_default()).await? {
-        x.ancestors = Some(x.ancestors.unwrap_or_default().replace(old_ancestors.as_str(), ancestors.as_str()));
-        Dept::update_by_map(rb, &x, value! {"id": &x.id}).await?;
-    }
+    let children = select_children_dept_by_id(rb, &id.unwrap_or_default()).await?;
 
-    if req.status == 1 && ancestors != "0" {
+    let status_sql = if req.status == 1 && ancestors != "0" {
         let ids = ancestors.split(",").map(|s| s.i64()).collect::<Vec<i64>>();
 
         let update_sql = format!(
-            "update sys_dept set status = ? ,update_time = ? where id in ({})",
+            "update sys_dept set status = ? ,update_time = ? where del_flag = 1 and id in ({})",
             ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", ")
         );
 
         let mut param = vec![value!(req.status), value!(DateTime::now())];
         param.extend(ids.iter().map(|&id| value!(id)));
+        Some((update_sql, param))
+    } else {
+        None
+    };
 
-        rb.exec(&update_sql, param).await?;
-    }
     req.ancestors = Some(ancestors.clone());
-
     let data = Dept::from(req);
 
-    Dept::update_by_map(rb, &data, value! {"id":  &id}).await.map(|_| ok_result())?
+    // 子部门的祖级列表、上级部门的状态和当前部门要么全部更新成功,要么全部不更新
+    in_transaction(|tx| {
+        Box::pin(async move {
+            for mut x in children {
+                x.ancestors = Some(x.ancestors.unwrap_or_default().replace(old_ancestors.as_str(), ancestors.as_str()));
+                Dept::update_by_map(tx, &x, value! {"id": &x.id}).await?;
+            }
+
+            if let Some((update_sql, param)) = status_sql {
+                tx.exec(&update_sql, param).await?;
+            }
+
+            Dept::update_by_map(tx, &data, value! {"id": &id}).await?;
+            Ok(())
+        })
+    })
+    .await
+    .map(|_| ok_result())?
 }
 
 /*
@@ -147,7 +164,10 @@ pub async fn update_sys_dept_status(item: Json<UpdateDeptStatusReq>) -> AppResul
                 let ancestors = x.ancestors;
                 let ids = ancestors.unwrap_or_default().split(",").map(|s| s.i64()).collect::<Vec<i64>>();
 
-                let update_sql = format!("update sys_dept set status = ? where id in ({})", ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", "));
+                let update_sql = format!(
+                    "update sys_dept set status = ? where del_flag = 1 and id in ({})",
+                    ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", ")
+                );
 
                 let mut param = vec![value!(req.status)];
                 param.extend(ids.iter().map(|&id| value!(id)));
@@ -155,7 +175,10 @@ pub async fn update_sys_dept_status(item: Json<UpdateDeptStatusReq>) -> AppResul
             }
         }
     }
-    let update_sql = format!("update sys_dept set status = ? where id in ({})", req.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", "));
+    let update_sql = format!(
+        "update sys_dept set status = ? where del_flag = 1 and id in ({})",
+        req.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", ")
+    );
 
     let mut param = vec![value!(req.status)];
     param.extend(req.ids.iter().map(|&id| value!(id)));
@@ -189,14 +212,16 @@ pub async fn query_sys_dept_detail(item: Json<QueryDeptDetailReq>) -> AppResult<
  *date：2025/01/10 09:21:35
  */
 #[web::post("/dept/queryDeptList")]
-pub async fn query_sys_dept_list(item: Json<QueryDeptListReq>) -> AppResult<Response> {
+pub async fn query_sys_dept_list(req: web::HttpRequest, item: Json<QueryDeptListReq>) -> AppResult<Response> {
     info!("query sys_dept_list params: {:?}", &item);
     let rb = &mut RB.clone();
+    let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
+    let scope = query_data_scope(user_id).await?;
 
     let dept_name = item.dept_name.as_deref().unwrap_or_default(); //部门名称
     let status = item.status.unwrap_or(2); //部状态（0：停用，1:正常）
 
-    let result = Dept::select_page_dept_list(rb, dept_name, status).await?;
+    let result = Dept::select_page_dept_list(rb, dept_name, status, scope.all, &scope.dept_ids).await?;
 
     let mut list: Vec<DeptResp> = Vec::new();
     for x in result {
@@ -2,6 +2,8 @@ use crate::common::error::{AppError, AppResult};
 use crate::common::result::{ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_menu_model::{select_count_menu_by_parent_id, Menu};
 use crate::model::system::sys_role_menu_model::select_count_menu_by_menu_id;
+use crate::utils::path_pattern_util::PermissionRule;
+use crate::utils::permission_util::invalidate_permissions;
 use crate::vo::system::sys_menu_vo::*;
 use crate::RB;
 use log::info;
@@ -35,8 +37,17 @@ pub async fn add_sys_menu(item: Json<MenuReq>) -> AppResult<Response> {
         }
     }
 
+    if let Some(api_url) = req.api_url.clone() {
+        if !api_url.trim().is_empty() && PermissionRule::parse(&api_url).is_none() {
+            return Err(AppError::BusinessError("接口URL格式不正确"));
+        }
+    }
+
     req.id = None;
-    Menu::insert(rb, &Menu::from(req)).await.map(|_| ok_result())?
+    Menu::insert(rb, &Menu::from(req)).await?;
+
+    invalidate_permissions();
+    ok_result()
 }
 
 /*
@@ -61,7 +72,10 @@ pub async fn delete_sys_menu(item: Json<DeleteMenuReq>) -> AppResult<Response> {
         }
     }
 
-    Menu::delete_by_map(rb, value! {"id": &ids}).await.map(|_| ok_result())?
+    Menu::delete_by_map(rb, value! {"id": &ids}).await?;
+
+    invalidate_permissions();
+    ok_result()
 }
 
 /*
@@ -101,7 +115,16 @@ pub async fn update_sys_menu(item: Json<MenuReq>) -> AppResult<Response> {
         }
     }
 
-    Menu::update_by_map(rb, &Menu::from(req), value! {"id": &id}).await.map(|_| ok_result())?
+    if let Some(api_url) = req.api_url.clone() {
+        if !api_url.trim().is_empty() && PermissionRule::parse(&api_url).is_none() {
+            return Err(AppError::BusinessError("接口URL格式不正确"));
+        }
+    }
+
+    Menu::update_by_map(rb, &Menu::from(req), value! {"id": &id}).await?;
+
+    invalidate_permissions();
+    ok_result()
 }
 
 /*
@@ -123,6 +146,7 @@ pub async fn update_sys_menu_status(item: Json<UpdateMenuStatusReq>) -> AppResul
     param.extend(req.ids.iter().map(|&id| value!(id)));
     rb.exec(&update_sql, param).await?;
 
+    invalidate_permissions();
     ok_result()
 }
 
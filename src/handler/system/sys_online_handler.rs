@@ -0,0 +1,55 @@
+use crate::common::error::AppResult;
+use crate::common::result::{ok_result, ok_result_page};
+use crate::model::system::sys_user_token_model::revoke_token_family;
+use crate::utils::revoke_util::revoke_session;
+use crate::utils::session_util::{list_sessions, remove_session};
+use crate::vo::system::sys_online_vo::*;
+use crate::RB;
+use log::info;
+use ntex::http::Response;
+use ntex::web;
+use ntex::web::types::Json;
+
+/*
+ *查询在线用户列表
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/online/queryOnlineList")]
+pub async fn query_online_list(item: Json<QueryOnlineListReq>) -> AppResult<Response> {
+    info!("query online_list params: {:?}", &item);
+
+    let user_name = item.user_name.as_deref().unwrap_or_default();
+    let ipaddr = item.ipaddr.as_deref().unwrap_or_default();
+
+    let list = list_sessions()
+        .into_iter()
+        .filter(|x| user_name.is_empty() || x.user_name == user_name)
+        .filter(|x| ipaddr.is_empty() || x.ipaddr == ipaddr)
+        .collect::<Vec<_>>();
+
+    let total = list.len() as u64;
+    let skip = (item.page_no.max(1) - 1) * item.page_size;
+    let online_list = list.into_iter().skip(skip as usize).take(item.page_size as usize).map(|x| x.into()).collect::<Vec<OnlineResp>>();
+
+    ok_result_page(online_list, total)
+}
+
+/*
+ *强制退出(立即撤销会话的访问令牌和刷新令牌)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/online/forceLogout")]
+pub async fn force_logout(item: Json<ForceLogoutReq>) -> AppResult<Response> {
+    info!("force logout params: {:?}", &item);
+    let rb = &mut RB.clone();
+
+    for session_id in &item.session_ids {
+        revoke_session(session_id);
+        revoke_token_family(rb, session_id).await?;
+        remove_session(session_id);
+    }
+
+    ok_result()
+}
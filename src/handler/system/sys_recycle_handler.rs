@@ -0,0 +1,285 @@
+use crate::common::error::{AppError, AppResult};
+use crate::common::result::{ok_result, ok_result_page};
+use crate::model::system::sys_dept_model::Dept;
+use crate::model::system::sys_recycle_model::{count_recycle_list, select_recycle_list};
+use crate::model::system::sys_role_dept_model::RoleDept;
+use crate::model::system::sys_role_menu_model::RoleMenu;
+use crate::model::system::sys_role_model::Role;
+use crate::model::system::sys_user_login_fail_model::UserLoginFail;
+use crate::model::system::sys_user_mfa_model::UserMfa;
+use crate::model::system::sys_user_model::User;
+use crate::model::system::sys_user_password_history_model::UserPasswordHistory;
+use crate::model::system::sys_user_post_model::UserPost;
+use crate::model::system::sys_user_role_model::{delete_deleted_role_by_user_ids, UserRole};
+use crate::model::system::sys_user_token_model::UserToken;
+use crate::utils::permission_util::invalidate_permissions;
+use crate::utils::tx_util::in_transaction;
+use crate::vo::system::sys_recycle_vo::*;
+use crate::RB;
+use log::info;
+use ntex::http::Response;
+use ntex::web;
+use ntex::web::types::Json;
+use rbatis::rbdc::datetime::DateTime;
+use rbs::value;
+use std::collections::{HashMap, HashSet};
+
+/*
+ *查询回收站列表(逻辑删除的用户、角色、部门)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/recycle/queryList")]
+pub async fn query_recycle_list(item: Json<QueryRecycleListReq>) -> AppResult<Response> {
+    info!("query recycle_list params: {:?}", &item);
+    let rb = &mut RB.clone();
+
+    let recycle_type = item.recycle_type.map(|x| x.as_str()).unwrap_or_default(); //数据类型
+    let name = item.name.as_deref().unwrap_or_default(); //名称
+    let begin_time = item.begin_time.as_deref().unwrap_or_default(); //删除时间开始
+    let end_time = item.end_time.as_deref().unwrap_or_default(); //删除时间结束
+
+    let page_no = (item.page_no.max(1) - 1) * item.page_size;
+    let d = select_recycle_list(rb, recycle_type, name, begin_time, end_time, page_no, item.page_size).await?;
+
+    let mut list: Vec<RecycleResp> = Vec::new();
+    for x in d {
+        list.push(x.into())
+    }
+
+    let total = count_recycle_list(rb, recycle_type, name, begin_time, end_time).await?;
+    ok_result_page(list, total)
+}
+
+/*
+ *恢复回收站数据(重新校验唯一性和上级数据是否存在)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/recycle/restore")]
+pub async fn restore_recycle(item: Json<RecycleReq>) -> AppResult<Response> {
+    info!("restore recycle params: {:?}", &item);
+
+    if item.ids.is_empty() {
+        return Err(AppError::BusinessError("请选择要恢复的数据"));
+    }
+
+    match item.recycle_type {
+        RecycleType::User => restore_users(&item.ids).await?,
+        RecycleType::Role => restore_roles(&item.ids).await?,
+        RecycleType::Dept => restore_depts(&item.ids).await?,
+    }
+
+    invalidate_permissions();
+    ok_result()
+}
+
+/*
+ *彻底删除回收站数据(同时删除关联数据,不能恢复)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/recycle/purge")]
+pub async fn purge_recycle(item: Json<RecycleReq>) -> AppResult<Response> {
+    info!("purge recycle params: {:?}", &item);
+
+    if item.ids.is_empty() {
+        return Err(AppError::BusinessError("请选择要彻底删除的数据"));
+    }
+
+    match item.recycle_type {
+        RecycleType::User => purge_users(&item.ids).await?,
+        RecycleType::Role => purge_roles(&item.ids).await?,
+        RecycleType::Dept => purge_depts(&item.ids).await?,
+    }
+
+    invalidate_permissions();
+    ok_result()
+}
+
+async fn restore_users(ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let users = User::select_by_map(rb, value! {"id": ids, "del_flag": 0}).await?;
+    if users.len() != ids.iter().collect::<HashSet<_>>().len() {
+        return Err(AppError::BusinessError("回收站中不存在该用户"));
+    }
+
+    let mut user_names = HashSet::new();
+    let mut mobiles = HashSet::new();
+    let mut emails = HashSet::new();
+    for x in &users {
+        if !user_names.insert(&x.user_name) || User::select_by_user_name(rb, &x.user_name).await?.is_some() {
+            return Err(AppError::BusinessError("登录账号已存在,不能恢复"));
+        }
+
+        if !mobiles.insert(&x.mobile) || User::select_by_mobile(rb, &x.mobile).await?.is_some() {
+            return Err(AppError::BusinessError("手机号码已存在,不能恢复"));
+        }
+
+        if !emails.insert(&x.email) || User::select_by_email(rb, &x.email).await?.is_some() {
+            return Err(AppError::BusinessError("邮箱账号已存在,不能恢复"));
+        }
+
+        if Dept::select_by_id(rb, &x.dept_id).await?.is_none() {
+            return Err(AppError::BusinessError("用户所属部门不存在,请先恢复部门"));
+        }
+    }
+
+    let ids = ids.to_vec();
+    in_transaction(|tx| {
+        Box::pin(async move {
+            for mut x in users {
+                x.del_flag = 1;
+                x.update_time = Some(DateTime::now());
+                User::update_by_map(tx, &x, value! {"id": &x.id}).await?;
+            }
+            // 用户删除后角色也可能被删除,恢复用户时去掉已删除的角色,需要时重新分配
+            delete_deleted_role_by_user_ids(tx, &ids).await?;
+            Ok(())
+        })
+    })
+    .await
+}
+
+async fn restore_roles(ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let roles = Role::select_by_map(rb, value! {"id": ids, "del_flag": 0}).await?;
+    if roles.len() != ids.iter().collect::<HashSet<_>>().len() {
+        return Err(AppError::BusinessError("回收站中不存在该角色"));
+    }
+
+    let mut role_names = HashSet::new();
+    let mut role_keys = HashSet::new();
+    for x in &roles {
+        if !role_names.insert(&x.role_name) || Role::select_by_role_name(rb, &x.role_name).await?.is_some() {
+            return Err(AppError::BusinessError("角色名称已存在,不能恢复"));
+        }
+
+        if !role_keys.insert(&x.role_key) || Role::select_by_role_key(rb, &x.role_key).await?.is_some() {
+            return Err(AppError::BusinessError("角色权限已存在,不能恢复"));
+        }
+    }
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            for mut x in roles {
+                x.del_flag = Some(1);
+                x.update_time = Some(DateTime::now());
+                Role::update_by_map(tx, &x, value! {"id": &x.id}).await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+async fn restore_depts(ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let mut depts = Dept::select_by_map(rb, value! {"id": ids, "del_flag": 0}).await?;
+    if depts.len() != ids.iter().collect::<HashSet<_>>().len() {
+        return Err(AppError::BusinessError("回收站中不存在该部门"));
+    }
+
+    // 上级部门先恢复,上级部门可能被移动过,祖级列表按照恢复时的上级部门重新计算
+    depts.sort_by_key(|x| x.ancestors.as_deref().unwrap_or_default().split(',').count());
+
+    let mut ancestors: HashMap<i64, String> = HashMap::new();
+    let mut dept_names = HashSet::new();
+    for x in depts.iter_mut() {
+        let parent_ancestors = if x.parent_id == 0 {
+            None
+        } else if let Some(parent) = ancestors.get(&x.parent_id) {
+            Some(parent.clone())
+        } else {
+            match Dept::select_by_id(rb, &x.parent_id).await? {
+                None => return Err(AppError::BusinessError("上级部门不存在,请先恢复上级部门")),
+                Some(parent) => parent.ancestors,
+            }
+        };
+
+        if !dept_names.insert((x.parent_id, x.dept_name.clone())) || Dept::select_by_dept_name(rb, &x.dept_name, x.parent_id).await?.is_some() {
+            return Err(AppError::BusinessError("部门名称已存在,不能恢复"));
+        }
+
+        if let Some(parent_ancestors) = parent_ancestors {
+            x.ancestors = Some(format!("{},{}", parent_ancestors, x.parent_id));
+        }
+        ancestors.insert(x.id.unwrap_or_default(), x.ancestors.clone().unwrap_or_default());
+    }
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            for mut x in depts {
+                x.del_flag = Some(1);
+                x.update_time = Some(DateTime::now());
+                Dept::update_by_map(tx, &x, value! {"id": &x.id}).await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+async fn purge_users(ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let ids = User::select_by_map(rb, value! {"id": ids, "del_flag": 0}).await?.into_iter().filter_map(|x| x.id).collect::<Vec<i64>>();
+    if ids.is_empty() {
+        return Err(AppError::BusinessError("回收站中不存在该用户"));
+    }
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            UserRole::delete_by_map(tx, value! {"user_id": &ids}).await?;
+            UserPost::delete_by_map(tx, value! {"user_id": &ids}).await?;
+            UserMfa::delete_by_map(tx, value! {"user_id": &ids}).await?;
+            UserPasswordHistory::delete_by_map(tx, value! {"user_id": &ids}).await?;
+            UserToken::delete_by_map(tx, value! {"user_id": &ids}).await?;
+            UserLoginFail::delete_by_map(tx, value! {"user_id": &ids}).await?;
+            User::delete_by_map(tx, value! {"id": &ids, "del_flag": 0}).await?;
+            Ok(())
+        })
+    })
+    .await
+}
+
+async fn purge_roles(ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let ids = Role::select_by_map(rb, value! {"id": ids, "del_flag": 0}).await?.into_iter().filter_map(|x| x.id).collect::<Vec<i64>>();
+    if ids.is_empty() {
+        return Err(AppError::BusinessError("回收站中不存在该角色"));
+    }
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            RoleMenu::delete_by_map(tx, value! {"role_id": &ids}).await?;
+            RoleDept::delete_by_map(tx, value! {"role_id": &ids}).await?;
+            UserRole::delete_by_map(tx, value! {"role_id": &ids}).await?;
+            Role::delete_by_map(tx, value! {"id": &ids, "del_flag": 0}).await?;
+            Ok(())
+        })
+    })
+    .await
+}
+
+async fn purge_depts(ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    let ids = Dept::select_by_map(rb, value! {"id": ids, "del_flag": 0}).await?.into_iter().filter_map(|x| x.id).collect::<Vec<i64>>();
+    if ids.is_empty() {
+        return Err(AppError::BusinessError("回收站中不存在该部门"));
+    }
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            RoleDept::delete_by_map(tx, value! {"dept_id": &ids}).await?;
+            Dept::delete_by_map(tx, value! {"id": &ids, "del_flag": 0}).await?;
+            Ok(())
+        })
+    })
+    .await
+}
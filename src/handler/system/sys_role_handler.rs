@@ -1,11 +1,15 @@
 use crate::common::error::{AppError, AppResult};
 use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::model::system::sys_dept_model::Dept;
 use crate::model::system::sys_menu_model::Menu;
 use crate::model::system::sys_role_dept_model::RoleDept;
 use crate::model::system::sys_role_menu_model::{query_menu_by_role, RoleMenu};
-use crate::model::system::sys_role_model::Role;
+use crate::model::system::sys_role_model::{soft_delete_role_by_ids, Role};
 use crate::model::system::sys_user_model::{count_allocated_list, count_unallocated_list, select_allocated_list, select_unallocated_list};
 use crate::model::system::sys_user_role_model::{count_user_role_by_role_id, delete_user_role_by_role_id_user_id, UserRole};
+use crate::utils::data_scope_util::query_data_scope;
+use crate::utils::permission_util::invalidate_permissions;
+use crate::utils::tx_util::in_transaction;
 use crate::vo::system::sys_role_vo::*;
 use crate::vo::system::sys_user_vo::UserResp;
 use crate::RB;
@@ -16,6 +20,7 @@ use ntex::web::types::Json;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbdc::datetime::DateTime;
 use rbs::value;
+use std::collections::HashSet;
 /*
  *添加角色信息
  *author：刘飞华
@@ -50,6 +55,9 @@ pub async fn delete_sys_role(item: Json<DeleteRoleReq>) -> AppResult<Response> {
     let rb = &mut RB.clone();
 
     let ids = item.ids.clone();
+    if ids.is_empty() {
+        return Err(AppError::BusinessError("请选择要删除的角色"));
+    }
 
     if ids.contains(&1) {
         return Err(AppError::BusinessError("不允许操作超级管理员角色"));
@@ -69,9 +77,11 @@ pub async fn delete_sys_role(item: Json<DeleteRoleReq>) -> AppResult<Response> {
         }
     }
 
-    RoleMenu::delete_by_map(rb, value! {"role_id": &item.ids}).await?;
-    RoleDept::delete_by_map(rb, value! {"role_id": &item.ids}).await?;
-    Role::delete_by_map(rb, value! {"id": &item.ids}).await.map(|_| ok_result())?
+    // 逻辑删除,保留角色的菜单和数据权限,从回收站恢复后可以直接使用
+    soft_delete_role_by_ids(rb, &item.ids).await?;
+
+    invalidate_permissions();
+    ok_result()
 }
 
 /*
@@ -110,7 +120,10 @@ pub async fn update_sys_role(item: Json<RoleReq>) -> AppResult<Response> {
         }
     }
 
-    Role::update_by_map(rb, &Role::from(req), value! {"id": &id}).await.map(|_| ok_result())?
+    Role::update_by_map(rb, &Role::from(req), value! {"id": &id}).await?;
+
+    invalidate_permissions();
+    ok_result()
 }
 
 /*
@@ -127,11 +140,17 @@ pub async fn update_sys_role_status(item: Json<UpdateRoleStatusReq>) -> AppResul
         return Err(AppError::BusinessError("不允许操作超级管理员角色"));
     }
 
-    let update_sql = format!("update sys_role set status = ? where id in ({})", item.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", "));
+    let update_sql = format!(
+        "update sys_role set status = ? where del_flag = 1 and id in ({})",
+        item.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", ")
+    );
 
     let mut param = vec![value!(item.status)];
     param.extend(item.ids.iter().map(|&id| value!(id)));
-    rb.exec(&update_sql, param).await.map(|_| ok_result())?
+    rb.exec(&update_sql, param).await?;
+
+    invalidate_permissions();
+    ok_result()
 }
 
 /*
@@ -237,10 +256,6 @@ pub async fn update_role_menu(item: Json<UpdateRoleMenuReq>) -> AppResult<Respon
         return Err(AppError::BusinessError("不允许操作超级管理员角色"));
     }
 
-    let rb = &mut RB.clone();
-
-    RoleMenu::delete_by_map(rb, value! {"role_id": &role_id}).await?;
-
     let mut role_menu: Vec<RoleMenu> = Vec::new();
 
     for id in &item.menu_ids {
@@ -253,7 +268,86 @@ pub async fn update_role_menu(item: Json<UpdateRoleMenuReq>) -> AppResult<Respon
         })
     }
 
-    RoleMenu::insert_batch(rb, &role_menu, item.menu_ids.len() as u64).await?;
+    in_transaction(|tx| {
+        Box::pin(async move {
+            RoleMenu::delete_by_map(tx, value! {"role_id": &role_id}).await?;
+            if !role_menu.is_empty() {
+                RoleMenu::insert_batch(tx, &role_menu, role_menu.len() as u64).await?;
+            }
+            Ok(())
+        })
+    })
+    .await?;
+
+    invalidate_permissions();
+    ok_result()
+}
+
+/*
+ *查询角色关联的部门(自定数据权限)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/role/queryRoleDept")]
+pub async fn query_role_dept(item: Json<QueryRoleDeptReq>) -> AppResult<Response> {
+    info!("query role_dept params: {:?}", &item);
+    let rb = &mut RB.clone();
+
+    // 查询所有部门
+    let dept_list_all = Dept::select_by_map(rb, value! {"del_flag": 1}).await?;
+
+    let mut dept_list: Vec<DeptDataList> = Vec::new();
+    for x in dept_list_all {
+        let id = x.id.unwrap_or_default();
+        dept_list.push(DeptDataList {
+            id,
+            parent_id: x.parent_id,
+            title: x.dept_name.clone(),
+            key: id.to_string(),
+            label: x.dept_name,
+        });
+    }
+
+    let dept_ids = RoleDept::select_by_map(rb, value! {"role_id": &item.role_id}).await?.into_iter().map(|x| x.dept_id).collect();
+
+    ok_result_data(QueryRoleDeptData { dept_ids, dept_list })
+}
+
+/*
+ *更新角色关联的部门(自定数据权限)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/role/updateRoleDept")]
+pub async fn update_role_dept(item: Json<UpdateRoleDeptReq>) -> AppResult<Response> {
+    info!("update role_dept params: {:?}", &item);
+    let role_id = item.role_id;
+
+    if role_id == 1 {
+        return Err(AppError::BusinessError("不允许操作超级管理员角色"));
+    }
+
+    let rb = &mut RB.clone();
+
+    if Role::select_by_id(rb, &role_id).await?.is_none() {
+        return Err(AppError::BusinessError("角色不存在"));
+    }
+
+    let mut role_dept: Vec<RoleDept> = Vec::new();
+    for dept_id in item.dept_ids.iter().collect::<HashSet<&i64>>() {
+        role_dept.push(RoleDept { role_id, dept_id: *dept_id })
+    }
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            RoleDept::delete_by_map(tx, value! {"role_id": &role_id}).await?;
+            if !role_dept.is_empty() {
+                RoleDept::insert_batch(tx, &role_dept, role_dept.len() as u64).await?;
+            }
+            Ok(())
+        })
+    })
+    .await?;
 
     ok_result()
 }
@@ -264,10 +358,12 @@ pub async fn update_role_menu(item: Json<UpdateRoleMenuReq>) -> AppResult<Respon
  *date：2025/01/10 09:21:35
  */
 #[web::post("/role/queryAllocatedList")]
-pub async fn query_allocated_list(item: Json<AllocatedListReq>) -> AppResult<Response> {
+pub async fn query_allocated_list(req: web::HttpRequest, item: Json<AllocatedListReq>) -> AppResult<Response> {
     info!("update role_menu params: {:?}", &item);
 
     let rb = &mut RB.clone();
+    let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
+    let scope = query_data_scope(user_id).await?;
 
     let page_no = item.page_no;
     let page_size = item.page_size;
@@ -276,14 +372,14 @@ pub async fn query_allocated_list(item: Json<AllocatedListReq>) -> AppResult<Res
     let user_name = item.user_name.as_deref().unwrap_or_default();
 
     let page_no = (page_no - 1) * page_size;
-    let d = select_allocated_list(rb, role_id, user_name, mobile, page_no, page_size).await?;
+    let d = select_allocated_list(rb, role_id, user_name, mobile, scope.all, &scope.dept_ids, page_no, page_size).await?;
 
     let mut sys_user_list_data: Vec<UserResp> = Vec::new();
     for x in d {
         sys_user_list_data.push(x.into())
     }
 
-    let total = count_allocated_list(rb, role_id, user_name, mobile).await?;
+    let total = count_allocated_list(rb, role_id, user_name, mobile, scope.all, &scope.dept_ids).await?;
     ok_result_page(sys_user_list_data, total)
 }
 
@@ -293,10 +389,12 @@ pub async fn query_allocated_list(item: Json<AllocatedListReq>) -> AppResult<Res
  *date：2025/01/10 09:21:35
  */
 #[web::post("/role/queryUnallocatedList")]
-pub async fn query_unallocated_list(item: Json<UnallocatedListReq>) -> AppResult<Response> {
+pub async fn query_unallocated_list(req: web::HttpRequest, item: Json<UnallocatedListReq>) -> AppResult<Response> {
     info!("update role_menu params: {:?}", &item);
 
     let rb = &mut RB.clone();
+    let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
+    let scope = query_data_scope(user_id).await?;
 
     let page_no = item.page_no;
     let page_size = item.page_size;
@@ -305,14 +403,14 @@ pub async fn query_unallocated_list(item: Json<UnallocatedListReq>) -> AppResult
     let user_name = item.user_name.as_deref().unwrap_or_default();
 
     let page_no = (page_no - 1) * page_size;
-    let d = select_unallocated_list(rb, role_id, user_name, mobile, page_no, page_size).await?;
+    let d = select_unallocated_list(rb, role_id, user_name, mobile, scope.all, &scope.dept_ids, page_no, page_size).await?;
 
     let mut sys_user_list_data: Vec<UserResp> = Vec::new();
     for x in d {
         sys_user_list_data.push(x.into())
     }
 
-    let total = count_unallocated_list(rb, role_id, user_name, mobile).await?;
+    let total = count_unallocated_list(rb, role_id, user_name, mobile, scope.all, &scope.dept_ids).await?;
     ok_result_page(sys_user_list_data, total)
 }
 
@@ -329,6 +427,7 @@ pub async fn cancel_auth_user(item: Json<CancelAuthUserReq>) -> AppResult<Respon
 
     delete_user_role_by_role_id_user_id(rb, item.role_id, item.user_id).await?;
 
+    invalidate_permissions();
     ok_result()
 }
 
@@ -352,6 +451,7 @@ pub async fn batch_cancel_auth_user(item: Json<CancelAuthUserAllReq>) -> AppResu
     param.extend(item.user_ids.iter().map(|&id| value!(id)));
     rb.exec(&update_sql, param).await?;
 
+    invalidate_permissions();
     ok_result()
 }
 
@@ -381,5 +481,6 @@ pub async fn batch_auth_user(item: Json<SelectAuthUserAllReq>) -> AppResult<Resp
 
     UserRole::insert_batch(rb, &user_role, item.user_ids.len() as u64).await?;
 
+    invalidate_permissions();
     ok_result()
 }
@@ -1,27 +1,56 @@
+use crate::common::config::APP_CONFIG;
 use crate::common::error::{AppError, AppResult};
 use crate::common::result::{err_result_msg, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_dept_model::Dept;
 use crate::model::system::sys_login_log_model::LoginLog;
 use crate::model::system::sys_menu_model::Menu;
 use crate::model::system::sys_role_model::Role;
-use crate::model::system::sys_user_model::User;
+use crate::model::system::sys_user_mfa_model::UserMfa;
+use crate::model::system::sys_user_model::{soft_delete_user_by_ids, User};
+use crate::model::system::sys_user_password_history_model::UserPasswordHistory;
 use crate::model::system::sys_user_post_model::UserPost;
-use crate::model::system::sys_user_role_model::{is_admin, UserRole};
-use crate::utils::jwt_util::JwtToken;
+use crate::model::system::sys_user_role_model::UserRole;
+use crate::model::system::sys_user_token_model::{revoke_token_family, revoke_user_token, UserToken};
+use crate::utils::captcha_util::verify_captcha;
+use crate::utils::data_scope_util::query_data_scope;
+use crate::utils::ip_region_util::ip_location;
+use crate::utils::ip_util::client_ip;
+use crate::utils::jwt_util::{JwtToken, JWT_CONFIG};
+use crate::utils::login_lock_util::{check_locked, clear_failure, has_recent_failure, lock_info, record_failure, record_ip_failure, unlock_user};
+use crate::utils::mail_util::send_mail;
+use crate::utils::metrics_util::record_login;
+use crate::utils::password_policy_util::{create_change_token, get_change_token, remove_change_token, CHANGE_TOKEN_EXPIRE_SECS, PASSWORD_POLICY};
+use crate::utils::password_util::{hash_password, is_hashed, verify_password};
+use crate::utils::permission_util::{get_permissions, invalidate_permissions, permission_version};
+use crate::utils::reset_token_util::{allow_reset_mail, create_reset_token, get_reset_token, remove_reset_token};
+use crate::utils::revoke_util::{revoke_token, revoke_user};
+use crate::utils::session_util::{remove_session, remove_user_sessions, save_session, session_login_time};
+use crate::utils::token_util::{random_token, sha256_hex};
+use crate::utils::totp_util::{generate_secret, otpauth_uri, qr_png, verify_code};
+use crate::utils::two_factor_util::{create_challenge, fail_challenge, get_challenge, remove_challenge, CHALLENGE_EXPIRE_SECS};
+use crate::utils::tx_util::in_transaction;
 use crate::utils::user_agent_util::UserAgentUtil;
 use crate::vo::system::sys_dept_vo::DeptResp;
 use crate::vo::system::sys_role_vo::RoleResp;
 use crate::vo::system::sys_user_vo::*;
 use crate::RB;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use log::info;
 use ntex::http::Response;
 use ntex::web;
 use ntex::web::types::Json;
+use rbatis::executor::Executor;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbatis_codegen::ops::AsProxy;
 use rbatis::rbdc::datetime::DateTime;
 use rbs::value;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
+use std::time::Duration;
+
+// 双因素认证恢复码数量
+const RECOVERY_CODE_COUNT: usize = 10;
+
 /*
  *添加用户信息
  *author：刘飞华
@@ -45,16 +74,32 @@ pub async fn add_sys_user(item: Json<UserReq>) -> AppResult<Response> {
         return Err(AppError::BusinessError("邮箱账号已存在"));
     }
 
+    check_new_password(&req.user_name, &req.password, None).await?;
+
     let post_ids = req.post_ids.clone();
     req.id = None;
-    let id = User::insert(rb, &User::from(req)).await?.last_insert_id;
-
-    let mut user_post_list: Vec<UserPost> = Vec::new();
-    for post_id in post_ids {
-        user_post_list.push(UserPost { user_id: id.i64(), post_id })
-    }
+    req.password = hash_password(&req.password)?;
+    let mut user = User::from(req);
+    user.pwd_update_date = Some(DateTime::now());
+
+    in_transaction(|tx| {
+        Box::pin(async move {
+            let id = User::insert(tx, &user).await?.last_insert_id.i64();
+            add_password_history(tx, id, &user.password).await?;
+
+            let mut user_post_list: Vec<UserPost> = Vec::new();
+            for post_id in post_ids {
+                user_post_list.push(UserPost { user_id: id, post_id })
+            }
+            if !user_post_list.is_empty() {
+                UserPost::insert_batch(tx, &user_post_list, user_post_list.len() as u64).await?;
+            }
+            Ok(())
+        })
+    })
+    .await?;
 
-    UserPost::insert_batch(rb, &user_post_list, user_post_list.len() as u64).await.map(|_| ok_result())?
+    ok_result()
 }
 
 /*
@@ -65,13 +110,15 @@ pub async fn add_sys_user(item: Json<UserReq>) -> AppResult<Response> {
 #[web::post("/user/deleteUser")]
 pub async fn delete_sys_user(req: web::HttpRequest, item: Json<DeleteUserReq>) -> AppResult<Response> {
     info!("delete sys_user params: {:?}", &item);
-    let rb = &mut RB.clone();
 
     let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
 
     info!("query user menu params user_id {:?}", user_id);
 
     let ids = item.ids.clone();
+    if ids.is_empty() {
+        return Err(AppError::BusinessError("请选择要删除的用户"));
+    }
     if ids.contains(&user_id) {
         return Err(AppError::BusinessError("当前用户不能删除"));
     }
@@ -79,9 +126,12 @@ pub async fn delete_sys_user(req: web::HttpRequest, item: Json<DeleteUserReq>) -
         return Err(AppError::BusinessError("不允许操作超级管理员用户"));
     }
 
-    UserRole::delete_by_map(rb, value! {"user_id": &ids}).await?;
-    UserPost::delete_by_map(rb, value! {"user_id": &ids}).await?;
-    User::delete_by_map(rb, value! {"id": &ids}).await.map(|_| ok_result())?
+    // 逻辑删除,保留用户的角色、岗位等关联数据,从回收站恢复后可以直接使用
+    soft_delete_user_by_ids(&RB.clone(), &ids).await?;
+
+    invalidate_permissions();
+    revoke_user_tokens(&item.ids).await?;
+    ok_result()
 }
 
 /*
@@ -132,10 +182,23 @@ pub async fn update_sys_user(item: Json<UserReq>) -> AppResult<Response> {
         })
     }
 
-    UserPost::delete_by_map(rb, value! {"user_id": &id}).await?;
-    UserPost::insert_batch(rb, &user_post_list, user_post_list.len() as u64).await?;
+    //密码只能通过修改密码或重置密码接口变更
+    let mut data = User::from(req);
+    data.password = user.password;
 
-    User::update_by_map(rb, &User::from(req), value! {"id": &id}).await.map(|_| ok_result())?
+    in_transaction(|tx| {
+        Box::pin(async move {
+            UserPost::delete_by_map(tx, value! {"user_id": &id}).await?;
+            if !user_post_list.is_empty() {
+                UserPost::insert_batch(tx, &user_post_list, user_post_list.len() as u64).await?;
+            }
+            User::update_by_map(tx, &data, value! {"id": &id}).await?;
+            Ok(())
+        })
+    })
+    .await?;
+
+    ok_result()
 }
 
 /*
@@ -155,11 +218,32 @@ pub async fn update_sys_user_status(item: Json<UpdateUserStatusReq>) -> AppResul
         return Err(AppError::BusinessError("不允许操作超级管理员用户"));
     }
 
-    let update_sql = format!("update sys_user set status = ? where id in ({})", req.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", "));
+    let update_sql = format!(
+        "update sys_user set status = ? where del_flag = 1 and id in ({})",
+        req.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", ")
+    );
 
     let mut param = vec![value!(req.status)];
     param.extend(req.ids.iter().map(|&id| value!(id)));
-    rb.exec(&update_sql, param).await.map(|_| ok_result())?
+    rb.exec(&update_sql, param).await?;
+
+    if req.status == 0 {
+        revoke_user_tokens(&ids).await?;
+    }
+    ok_result()
+}
+
+/*
+ *解锁用户(清除登录失败锁定)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/unlockUser")]
+pub async fn unlock_sys_user(item: Json<UnlockUserReq>) -> AppResult<Response> {
+    info!("unlock sys_user params: {:?}", &item);
+
+    unlock_user(&item.ids).await?;
+    ok_result()
 }
 
 /*
@@ -184,8 +268,11 @@ pub async fn reset_sys_user_password(item: Json<ResetUserPwdReq>) -> AppResult<R
         None => Err(AppError::BusinessError("用户不存在")),
         Some(x) => {
             let mut user = x;
-            user.password = req.password;
-            User::update_by_map(rb, &user, value! {"id": &user.id}).await.map(|_| ok_result())?
+            check_new_password(&user.user_name, &req.password, Some(&user)).await?;
+            save_password(&mut user, &req.password).await?;
+
+            revoke_user_tokens(&[id]).await?;
+            ok_result()
         }
     }
 }
@@ -209,15 +296,197 @@ pub async fn update_sys_user_password(http_req: web::HttpRequest, item: Json<Upd
         None => Err(AppError::BusinessError("用户不存在")),
         Some(x) => {
             let mut user = x;
-            if user.password != req.pwd {
+            if !verify_password(&req.pwd, &user.password) {
                 return Err(AppError::BusinessError("旧密码不正确"));
             }
-            user.password = req.re_pwd;
-            User::update_by_map(rb, &user, value! {"id": &user.id}).await.map(|_| ok_result())?
+            check_new_password(&user.user_name, &req.re_pwd, Some(&user)).await?;
+            save_password(&mut user, &req.re_pwd).await?;
+
+            revoke_user_tokens(&[user_id]).await?;
+            ok_result()
+        }
+    }
+}
+
+/*
+ *密码过期后修改密码(登录时返回的修改令牌,修改成功后需要重新登录)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/changeExpiredPassword")]
+pub async fn change_expired_password(item: Json<ChangeExpiredPwdReq>) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+
+    let user_id = match get_change_token(&item.change_token) {
+        None => return Err(AppError::BusinessError("修改密码已超时,请重新登录")),
+        Some(x) => x,
+    };
+    info!("change expired_password params user_id: {:?}", user_id);
+
+    match User::select_by_id(rb, user_id).await? {
+        None => Err(AppError::BusinessError("用户不存在")),
+        Some(x) => {
+            let mut user = x;
+            check_new_password(&user.user_name, &item.re_pwd, Some(&user)).await?;
+            save_password(&mut user, &item.re_pwd).await?;
+            remove_change_token(&item.change_token);
+
+            revoke_user_tokens(&[user_id]).await?;
+            ok_result()
+        }
+    }
+}
+
+/*
+ *忘记密码,发送重置密码邮件(邮箱是否存在都返回成功,避免泄露用户信息)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/forgotPassword")]
+pub async fn forgot_password(item: Json<ForgotPasswordReq>) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+
+    let email = item.email.trim();
+    if email.is_empty() {
+        return Err(AppError::BusinessError("邮箱不能为空"));
+    }
+
+    //验证码限制同一IP的请求频率,同一邮箱在发送间隔内只发送一次
+    if item.captcha_key.is_empty() || item.captcha_code.is_empty() {
+        return Err(AppError::BusinessError("请输入验证码"));
+    }
+    if !verify_captcha(&item.captcha_key, &item.captcha_code) {
+        return Err(AppError::BusinessError("验证码错误"));
+    }
+    if !allow_reset_mail(email) {
+        info!("forgot password mail sent recently, skip");
+        return ok_result();
+    }
+
+    match User::select_by_email(rb, email).await? {
+        Some(user) if user.status == 1 => {
+            let token = create_reset_token(user.id.unwrap_or_default());
+            let config = &APP_CONFIG.password;
+            let body = format!(
+                "{}您好:\n\n您正在重置登录密码,重置密码令牌: {}\n{}{}\n\n令牌{}分钟内有效且只能使用一次,如果不是本人操作请忽略本邮件。",
+                user.nick_name,
+                token,
+                config.reset_url,
+                token,
+                config.reset_expire_secs / 60
+            );
+            let to = user.email.clone();
+            ntex::rt::spawn(async move {
+                if let Err(err) = send_mail(&to, "重置密码", &body).await {
+                    log::error!("send reset password mail error: {}", err);
+                }
+            });
+        }
+        _ => info!("forgot password email not found or user disabled, skip"),
+    }
+
+    ok_result()
+}
+
+/*
+ *通过邮件中的令牌重置密码(令牌只能使用一次)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/resetPasswordByToken")]
+pub async fn reset_password_by_token(item: Json<ResetPasswordByTokenReq>) -> AppResult<Response> {
+    info!("reset password_by_token params: {:?}", &item);
+    let rb = &mut RB.clone();
+
+    let user_id = match get_reset_token(&item.token) {
+        None => return Err(AppError::BusinessError("重置密码令牌无效或已过期")),
+        Some(x) => x,
+    };
+
+    match User::select_by_id(rb, user_id).await? {
+        None => Err(AppError::BusinessError("用户不存在")),
+        Some(x) => {
+            let mut user = x;
+            check_new_password(&user.user_name, &item.password, Some(&user)).await?;
+            if !remove_reset_token(&item.token) {
+                return Err(AppError::BusinessError("重置密码令牌无效或已过期"));
+            }
+            save_password(&mut user, &item.password).await?;
+
+            unlock_user(&[user_id]).await?;
+            revoke_user_tokens(&[user_id]).await?;
+            ok_result()
         }
     }
 }
 
+/*
+ *校验新密码是否符合密码策略(包括不能与当前密码和最近使用过的密码相同)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub(crate) async fn check_new_password(user_name: &str, password: &str, user: Option<&User>) -> AppResult<()> {
+    let policy = &PASSWORD_POLICY;
+    policy.validate(user_name, password).map_err(AppError::PasswordPolicyError)?;
+
+    let user = match user {
+        Some(x) if policy.history_count > 0 => x,
+        _ => return Ok(()),
+    };
+
+    let rb = &mut RB.clone();
+    let history = UserPasswordHistory::select_by_user_id(rb, &user.id.unwrap_or_default()).await?;
+    if verify_password(password, &user.password) || history.iter().take(policy.history_count).any(|x| verify_password(password, &x.password)) {
+        return Err(AppError::PasswordPolicyError(format!("不能使用最近{}次使用过的密码", policy.history_count)));
+    }
+    Ok(())
+}
+
+/*
+ *保存新密码,记录密码修改时间和历史密码
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub(crate) async fn save_password(user: &mut User, password: &str) -> AppResult<()> {
+    user.password = hash_password(password)?;
+    user.pwd_update_date = Some(DateTime::now());
+
+    let data = user.clone();
+    in_transaction(|tx| {
+        Box::pin(async move {
+            User::update_by_map(tx, &data, value! {"id": &data.id}).await?;
+            add_password_history(tx, data.id.unwrap_or_default(), &data.password).await
+        })
+    })
+    .await
+}
+
+/*
+ *添加历史密码,只保留策略需要的条数
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub(crate) async fn add_password_history(rb: &dyn Executor, user_id: i64, password: &str) -> AppResult<()> {
+    let history = UserPasswordHistory {
+        id: None,                           //主键
+        user_id,                            //用户ID
+        password: password.to_string(),     //密码哈希
+        create_time: Some(DateTime::now()), //创建时间
+    };
+    UserPasswordHistory::insert(rb, &history).await?;
+
+    let expired_ids = UserPasswordHistory::select_by_user_id(rb, &user_id)
+        .await?
+        .into_iter()
+        .skip(PASSWORD_POLICY.history_count)
+        .filter_map(|x| x.id)
+        .collect::<Vec<i64>>();
+    if !expired_ids.is_empty() {
+        UserPasswordHistory::delete_by_map(rb, value! {"id": &expired_ids}).await?;
+    }
+    Ok(())
+}
+
 /*
  *查询用户信息详情
  *author：刘飞华
@@ -256,9 +525,11 @@ pub async fn query_sys_user_detail(item: Json<QueryUserDetailReq>) -> AppResult<
  *date：2025/01/10 09:21:35
  */
 #[web::post("/user/queryUserList")]
-pub async fn query_sys_user_list(item: Json<QueryUserListReq>) -> AppResult<Response> {
+pub async fn query_sys_user_list(req: web::HttpRequest, item: Json<QueryUserListReq>) -> AppResult<Response> {
     info!("query sys_user_list params: {:?}", &item);
     let rb = &mut RB.clone();
+    let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
+    let scope = query_data_scope(user_id).await?;
 
     let mobile = item.mobile.as_deref().unwrap_or_default();
     let user_name = item.user_name.as_deref().unwrap_or_default();
@@ -266,12 +537,19 @@ pub async fn query_sys_user_list(item: Json<QueryUserListReq>) -> AppResult<Resp
     let dept_id = item.dept_id.unwrap_or_default();
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let d = User::select_sys_user_list(rb, page, mobile, user_name, status, dept_id).await?;
+    let d = User::select_sys_user_list(rb, page, mobile, user_name, status, dept_id, scope.all, &scope.dept_ids).await?;
 
     let total = d.total;
+    let user_ids = d.records.iter().filter_map(|x| x.id).collect::<Vec<i64>>();
+    let mut locks = lock_info(&user_ids).await?;
     let mut sys_user_list_data: Vec<UserResp> = Vec::new();
     for x in d.records {
-        sys_user_list_data.push(x.into())
+        let mut user: UserResp = x.into();
+        if let Some(lock) = locks.remove(&user.id.unwrap_or_default()) {
+            user.lock_status = 1;
+            user.lock_reason = Some(lock.reason);
+        }
+        sys_user_list_data.push(user)
     }
 
     ok_result_page(sys_user_list_data, total)
@@ -283,52 +561,452 @@ pub async fn query_sys_user_list(item: Json<QueryUserListReq>) -> AppResult<Resp
  *date：2025/01/10 09:21:35
  */
 #[web::post("/user/login")]
-pub async fn login(item: Json<UserLoginReq>) -> AppResult<Response> {
+pub async fn login(http_req: web::HttpRequest, item: Json<UserLoginReq>) -> AppResult<Response> {
     info!("user login params: {:?}", &item);
     let req = item.0;
     let rb = &mut RB.clone();
 
-    // let user_agent = headers.get("User-Agent").unwrap().to_str().unwrap();
-    let user_agent = "";
+    let user_agent = http_req.headers().get("User-Agent").map_or("", |x| x.to_str().unwrap_or_default());
     info!("user agent: {:?}", user_agent);
     let agent = UserAgentUtil::new(user_agent);
+    let ipaddr = client_ip(http_req.peer_addr(), http_req.headers());
 
     let user_result = User::select_by_account(rb, &req.account).await?;
     info!("query user by account: {:?}", user_result);
 
+    //开启验证码或者账号、IP最近登录失败过,需要校验验证码
+    if APP_CONFIG.captcha.enabled || has_recent_failure(user_result.as_ref().and_then(|x| x.id), &ipaddr).await? {
+        let captcha_key = req.captcha_key.as_deref().unwrap_or_default();
+        let captcha_code = req.captcha_code.as_deref().unwrap_or_default();
+        if captcha_key.is_empty() || captcha_code.is_empty() {
+            return Err(AppError::BusinessError("请输入验证码"));
+        }
+        if !verify_captcha(captcha_key, captcha_code) {
+            add_login_log(req.account, 0, "验证码错误", agent, &ipaddr).await;
+            return Err(AppError::BusinessError("验证码错误"));
+        }
+    }
+
     match user_result {
         None => {
-            add_login_log(req.account, 0, "用户不存在", agent).await;
+            record_ip_failure(&ipaddr);
+            add_login_log(req.account, 0, "用户不存在", agent, &ipaddr).await;
             Err(AppError::BusinessError("用户不存在"))
         }
         Some(user) => {
             let mut s_user = user.clone();
             let id = user.id.unwrap();
-            let username = user.user_name;
             let password = user.password;
 
-            if password.ne(&req.password) {
-                add_login_log(req.account, 0, "密码不正确", agent).await;
-                return err_result_msg("密码不正确");
+            if let Some(lock) = check_locked(id, &ipaddr).await? {
+                let msg = format!("账号已锁定({}),请{}分钟后重试", lock.reason, lock.remaining_secs.div_ceil(60));
+                add_login_log(req.account, 0, &msg, agent, &ipaddr).await;
+                return err_result_msg(&msg);
             }
 
-            let btn_menu = query_btn_menu(&id).await;
+            if user.status == 0 {
+                add_login_log(req.account, 0, "用户已被禁用", agent, &ipaddr).await;
+                return Err(AppError::BusinessError("用户已被禁用"));
+            }
 
-            if btn_menu.len() == 0 {
-                add_login_log(req.account, 0, "用户没有分配角色或者菜单,不能登录", agent).await;
+            if !verify_password(&req.password, &password) {
+                record_ip_failure(&ipaddr);
+                let msg = match record_failure(id, &ipaddr).await? {
+                    None => "密码不正确".to_string(),
+                    Some(lock) => format!("密码不正确,账号已锁定({}),请{}分钟后重试", lock.reason, lock.remaining_secs.div_ceil(60)),
+                };
+                add_login_log(req.account, 0, &msg, agent, &ipaddr).await;
+                return err_result_msg(&msg);
+            }
+            clear_failure(id, &ipaddr).await?;
+
+            if get_permissions(id).await?.is_empty() {
+                add_login_log(req.account, 0, "用户没有分配角色或者菜单,不能登录", agent, &ipaddr).await;
                 return Err(AppError::BusinessError("用户没有分配角色或者菜单,不能登录"));
             }
 
-            let token = JwtToken::new(id, &username, btn_menu).create_token("123")?;
+            //历史明文密码在校验通过后升级为哈希
+            if !is_hashed(&password) {
+                s_user.password = hash_password(&req.password)?;
+                User::update_by_map(rb, &s_user, value! {"id": &s_user.id}).await?;
+            }
+
+            //开启双因素认证后先返回挑战令牌,验证通过后再签发访问令牌
+            if let Some(mfa) = UserMfa::select_by_user_id(rb, &id).await? {
+                if mfa.status == 1 {
+                    let challenge_token = create_challenge(id, &req.account);
+                    return ok_result_data(TwoFactorLoginResp {
+                        two_factor: true,
+                        challenge_token,
+                        expires_in: CHALLENGE_EXPIRE_SECS,
+                    });
+                }
+            }
+
+            login_success(s_user, req.account, agent, ipaddr).await
+        }
+    }
+}
+
+/*
+ *登录成功,签发访问令牌并记录登录信息
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+async fn login_success(mut user: User, account: String, agent: UserAgentUtil, ipaddr: String) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+
+    //密码已过期只返回修改密码的令牌,修改成功后重新登录(没有修改记录的按创建时间计算)
+    let last_change = user.pwd_update_date.as_ref().or(user.create_time.as_ref()).map(|x| x.unix_timestamp());
+    if PASSWORD_POLICY.is_expired(last_change, DateTime::now().unix_timestamp()) {
+        add_login_log(account, 0, "密码已过期,需要修改密码", agent, &ipaddr).await;
+        return ok_result_data(PasswordExpiredResp {
+            password_expired: true,
+            change_token: create_change_token(user.id.unwrap_or_default()),
+            expires_in: CHANGE_TOKEN_EXPIRE_SECS,
+        });
+    }
+
+    let user_id = user.id.unwrap_or_default();
+    let session_id = random_token();
+    let (resp, _) = create_login_token(user_id, &user.user_name, &session_id).await?;
+    save_session(&session_id, user_id, &user.user_name, &ipaddr, &agent, DateTime::now(), resp.expires_in);
+
+    add_login_log(account, 1, "登录成功", agent.clone(), &ipaddr).await;
+    user.login_ip = ipaddr;
+    user.login_os = agent.os;
+    user.login_browser = agent.browser;
+    user.login_date = Some(DateTime::now());
+    User::update_by_map(rb, &user, value! {"id": &user.id}).await?;
+    ok_result_data(resp)
+}
+
+/*
+ *获取双因素认证密钥(扫码后调用开启接口确认)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/2fa/setup")]
+pub async fn setup_two_factor(req: web::HttpRequest) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+    let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
+    info!("setup two_factor params user_id: {:?}", user_id);
+
+    let user = match User::select_by_id(rb, user_id).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(x) => x,
+    };
+
+    let secret = generate_secret();
+    match UserMfa::select_by_user_id(rb, &user_id).await? {
+        Some(x) if x.status == 1 => return Err(AppError::BusinessError("已开启双因素认证,请联系管理员重置")),
+        Some(mut x) => {
+            x.secret = secret.clone();
+            x.update_time = Some(DateTime::now());
+            UserMfa::update_by_map(rb, &x, value! {"id": &x.id}).await?;
+        }
+        None => {
+            let mfa = UserMfa {
+                id: None,                           //主键
+                user_id,                            //用户ID
+                secret: secret.clone(),             //TOTP密钥(base32)
+                status: 0,                          //状态(0:待确认,1:已开启)
+                recovery_codes: "".to_string(),     //恢复码哈希(sha256,逗号分隔,使用后删除)
+                last_used_step: 0,                  //最后一次使用的时间步(防止验证码重复使用)
+                create_time: Some(DateTime::now()), //创建时间
+                update_time: None,                  //修改时间
+            };
+            UserMfa::insert(rb, &mfa).await?;
+        }
+    }
+
+    let otpauth_uri = otpauth_uri(&APP_CONFIG.totp.issuer, &user.user_name, &secret);
+    let qr_code = format!("data:image/png;base64,{}", STANDARD.encode(qr_png(&otpauth_uri)?));
+    ok_result_data(TwoFactorSetupResp { secret, otpauth_uri, qr_code })
+}
+
+/*
+ *开启双因素认证(校验身份验证器App中的验证码),返回恢复码
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/2fa/enable")]
+pub async fn enable_two_factor(req: web::HttpRequest, item: Json<TwoFactorEnableReq>) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+    let user_id = req.headers().get("userId").unwrap().to_str().unwrap().parse::<i64>().unwrap();
+    info!("enable two_factor params user_id: {:?}", user_id);
+
+    let mut mfa = match UserMfa::select_by_user_id(rb, &user_id).await? {
+        None => return Err(AppError::BusinessError("请先获取双因素认证密钥")),
+        Some(x) if x.status == 1 => return Err(AppError::BusinessError("已开启双因素认证")),
+        Some(x) => x,
+    };
+
+    let step = match verify_code(&mfa.secret, &item.code) {
+        None => return Err(AppError::BusinessError("验证码错误")),
+        Some(x) => x,
+    };
+
+    let recovery_codes = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code = random_token();
+            format!("{}-{}", &code[..5], &code[5..10])
+        })
+        .collect::<Vec<String>>();
+
+    mfa.status = 1;
+    mfa.recovery_codes = recovery_codes.iter().map(|x| sha256_hex(x)).collect::<Vec<String>>().join(",");
+    mfa.last_used_step = step as i64;
+    mfa.update_time = Some(DateTime::now());
+    UserMfa::update_by_map(rb, &mfa, value! {"id": &mfa.id}).await?;
+
+    ok_result_data(TwoFactorEnableResp { recovery_codes })
+}
+
+/*
+ *双因素认证(登录第二步),校验验证码或恢复码后签发访问令牌
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/2fa/verify")]
+pub async fn verify_two_factor(http_req: web::HttpRequest, item: Json<TwoFactorVerifyReq>) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+
+    let user_agent = http_req.headers().get("User-Agent").map_or("", |x| x.to_str().unwrap_or_default());
+    let agent = UserAgentUtil::new(user_agent);
+    let ipaddr = client_ip(http_req.peer_addr(), http_req.headers());
+
+    let challenge = match get_challenge(&item.challenge_token) {
+        None => return Err(AppError::BusinessError("认证已过期,请重新登录")),
+        Some(x) => x,
+    };
+    info!("verify two_factor params user_id: {:?}", challenge.user_id);
+
+    let user = User::select_by_id(rb, challenge.user_id).await?;
+    let mfa = UserMfa::select_by_user_id(rb, &challenge.user_id).await?;
+    let (user, mut mfa) = match (user, mfa) {
+        (Some(user), Some(mfa)) if mfa.status == 1 => (user, mfa),
+        _ => {
+            remove_challenge(&item.challenge_token);
+            return Err(AppError::BusinessError("认证已过期,请重新登录"));
+        }
+    };
+
+    //验证码错误会累计登录失败次数,锁定后不能继续尝试
+    if let Some(lock) = check_locked(challenge.user_id, &ipaddr).await? {
+        remove_challenge(&item.challenge_token);
+        let msg = format!("账号已锁定({}),请{}分钟后重试", lock.reason, lock.remaining_secs.div_ceil(60));
+        add_login_log(challenge.account, 0, &msg, agent, &ipaddr).await;
+        return err_result_msg(&msg);
+    }
+
+    let verified = match verify_code(&mfa.secret, &item.code) {
+        Some(step) if step as i64 > mfa.last_used_step => {
+            mfa.last_used_step = step as i64;
+            true
+        }
+        Some(_) => false,
+        None => use_recovery_code(&mut mfa, &item.code),
+    };
+
+    if !verified {
+        record_ip_failure(&ipaddr);
+        let msg = match record_failure(challenge.user_id, &ipaddr).await? {
+            None => {
+                fail_challenge(&item.challenge_token);
+                "双因素认证验证码错误".to_string()
+            }
+            Some(lock) => {
+                remove_challenge(&item.challenge_token);
+                format!("双因素认证验证码错误,账号已锁定({}),请{}分钟后重试", lock.reason, lock.remaining_secs.div_ceil(60))
+            }
+        };
+        add_login_log(challenge.account, 0, &msg, agent, &ipaddr).await;
+        return err_result_msg(&msg);
+    }
+    clear_failure(challenge.user_id, &ipaddr).await?;
+
+    //挑战令牌只能使用一次
+    if !remove_challenge(&item.challenge_token) {
+        return Err(AppError::BusinessError("认证已过期,请重新登录"));
+    }
+
+    mfa.update_time = Some(DateTime::now());
+    UserMfa::update_by_map(rb, &mfa, value! {"id": &mfa.id}).await?;
+
+    login_success(user, challenge.account, agent, ipaddr).await
+}
+
+/*
+ *使用恢复码(每个恢复码只能使用一次)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+fn use_recovery_code(mfa: &mut UserMfa, code: &str) -> bool {
+    let hash = sha256_hex(&code.trim().to_lowercase());
+    let mut codes = mfa.recovery_codes.split(',').filter(|x| !x.is_empty()).map(|x| x.to_string()).collect::<Vec<String>>();
+    match codes.iter().position(|x| *x == hash) {
+        None => false,
+        Some(i) => {
+            codes.remove(i);
+            mfa.recovery_codes = codes.join(",");
+            true
+        }
+    }
+}
+
+/*
+ *重置用户的双因素认证(管理员操作,用户需要重新绑定)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/2fa/reset")]
+pub async fn reset_two_factor(item: Json<TwoFactorResetReq>) -> AppResult<Response> {
+    info!("reset two_factor params: {:?}", &item);
+    let rb = &mut RB.clone();
+
+    UserMfa::delete_by_map(rb, value! {"user_id": &item.user_ids}).await?;
+    ok_result()
+}
+
+/*
+ *刷新访问令牌(刷新令牌每次使用后轮换,重复使用会撤销整个令牌族)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/refreshToken")]
+pub async fn refresh_token(http_req: web::HttpRequest, item: Json<RefreshTokenReq>) -> AppResult<Response> {
+    let rb = &mut RB.clone();
 
-            add_login_log(req.account, 1, "登录成功", agent.clone()).await;
-            s_user.login_os = agent.os;
-            s_user.login_browser = agent.browser;
-            s_user.login_date = Some(DateTime::now());
-            User::update_by_map(rb, &s_user, value! {"id": &s_user.id}).await?;
-            ok_result_data(token)
+    let old_token = match UserToken::select_by_token_hash(rb, &sha256_hex(&item.refresh_token)).await? {
+        None => return Err(AppError::BusinessError("刷新令牌无效")),
+        Some(x) => x,
+    };
+    info!("refresh token params user_id: {:?}", old_token.user_id);
+
+    if old_token.status != 1 {
+        if old_token.status == 2 {
+            log::warn!("refresh token reused, revoke token family of user_id: {:?}", old_token.user_id);
+            revoke_token_family(rb, &old_token.family_id).await?;
         }
+        return Err(AppError::BusinessError("刷新令牌已失效,请重新登录"));
+    }
+
+    if old_token.expire_time.as_ref().is_none_or(|x| x.unix_timestamp() < DateTime::now().unix_timestamp()) {
+        return Err(AppError::BusinessError("刷新令牌已过期,请重新登录"));
+    }
+
+    let user = match User::select_by_id(rb, old_token.user_id).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(x) => x,
+    };
+    if user.status == 0 {
+        revoke_token_family(rb, &old_token.family_id).await?;
+        return Err(AppError::BusinessError("用户已被禁用"));
     }
+
+    //并发刷新时只有一个请求能把令牌从有效改成已轮换,其余按重复使用处理
+    let rotated = rb.exec("update sys_user_token set status = 2 where id = ? and status = 1", vec![value!(old_token.id)]).await?;
+    if rotated.rows_affected == 0 {
+        log::warn!("refresh token reused, revoke token family of user_id: {:?}", old_token.user_id);
+        revoke_token_family(rb, &old_token.family_id).await?;
+        return Err(AppError::BusinessError("刷新令牌已失效,请重新登录"));
+    }
+
+    let (resp, new_id) = create_login_token(old_token.user_id, &user.user_name, &old_token.family_id).await?;
+
+    rb.exec("update sys_user_token set replaced_by = ? where id = ?", vec![value!(new_id), value!(old_token.id)]).await?;
+
+    //服务重启后会话丢失,登录时间取令牌族中第一个令牌的签发时间
+    let login_time = match session_login_time(&old_token.family_id) {
+        Some(x) => x,
+        None => UserToken::select_first_by_family_id(rb, &old_token.family_id)
+            .await?
+            .and_then(|x| x.create_time)
+            .unwrap_or_else(DateTime::now),
+    };
+    let user_agent = http_req.headers().get("User-Agent").map_or("", |x| x.to_str().unwrap_or_default());
+    let ipaddr = client_ip(http_req.peer_addr(), http_req.headers());
+    save_session(
+        &old_token.family_id,
+        user.id.unwrap_or_default(),
+        &user.user_name,
+        &ipaddr,
+        &UserAgentUtil::new(user_agent),
+        login_time,
+        resp.expires_in,
+    );
+
+    ok_result_data(resp)
+}
+
+/*
+ *退出登录
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[web::post("/user/logout")]
+pub async fn logout(req: web::HttpRequest) -> AppResult<Response> {
+    let rb = &mut RB.clone();
+
+    let authorization = req.headers().get("Authorization").map_or("", |x| x.to_str().unwrap_or_default());
+    let jwt_token = JwtToken::verify(&JWT_CONFIG, &authorization.replace("Bearer ", ""))?;
+
+    info!("user logout params user_id: {:?}", jwt_token.id);
+
+    revoke_token(&jwt_token.jti, jwt_token.exp);
+    revoke_token_family(rb, &jwt_token.sid).await?;
+    remove_session(&jwt_token.sid);
+    ok_result()
+}
+
+/*
+ *撤销用户所有的访问令牌和刷新令牌
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+async fn revoke_user_tokens(user_ids: &[i64]) -> AppResult<()> {
+    let rb = &mut RB.clone();
+
+    for user_id in user_ids {
+        revoke_user(*user_id);
+        revoke_user_token(rb, user_id).await?;
+        remove_user_sessions(*user_id);
+    }
+    Ok(())
+}
+
+/*
+ *生成访问令牌和刷新令牌
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+async fn create_login_token(user_id: i64, user_name: &str, family_id: &str) -> AppResult<(LoginResp, i64)> {
+    let rb = &mut RB.clone();
+
+    let token = JwtToken::new(&JWT_CONFIG, user_id, user_name, permission_version(), family_id).create_token(&JWT_CONFIG)?;
+    let new_refresh_token = random_token();
+    let expire_time = DateTime::now().add(Duration::from_secs(JWT_CONFIG.refresh_expire_secs));
+
+    let user_token = UserToken {
+        id: None,                                   //主键
+        user_id,                                    //用户ID
+        token_hash: sha256_hex(&new_refresh_token), //刷新令牌哈希(sha256)
+        family_id: family_id.to_string(),           //令牌族
+        replaced_by: None,                          //轮换后的新令牌ID
+        status: 1,                                  //状态(1:有效,2:已轮换,0:已撤销)
+        expire_time: Some(expire_time),             //过期时间
+        create_time: Some(DateTime::now()),         //创建时间
+        update_time: None,                          //修改时间
+    };
+    let id = UserToken::insert(rb, &user_token).await?.last_insert_id;
+
+    let resp = LoginResp {
+        token,
+        refresh_token: new_refresh_token,
+        expires_in: JWT_CONFIG.expire_secs,
+    };
+    Ok((resp, id.i64()))
 }
 
 /*
@@ -336,14 +1014,15 @@ pub async fn login(item: Json<UserLoginReq>) -> AppResult<Response> {
  *author：刘飞华
  *date：2025/01/10 09:21:35
  */
-async fn add_login_log(name: String, status: i8, msg: &str, agent: UserAgentUtil) {
+async fn add_login_log(name: String, status: i8, msg: &str, agent: UserAgentUtil, ipaddr: &str) {
     let rb = &mut RB.clone();
+    record_login(status == 1);
 
     let sys_login_log = LoginLog {
         id: None,                             //访问ID
         login_name: name,                     //登录账号
-        ipaddr: "todo".to_string(),           //登录IP地址
-        login_location: "todo".to_string(),   //登录地点
+        ipaddr: ipaddr.to_string(),           //登录IP地址
+        login_location: ip_location(ipaddr),  //登录地点
         platform: agent.platform,             //平台信息
         browser: agent.browser,               //浏览器类型
         version: agent.version,               //浏览器版本
@@ -363,34 +1042,6 @@ async fn add_login_log(name: String, status: i8, msg: &str, agent: UserAgentUtil
     }
 }
 
-/*
- *查询按钮权限
- *author：刘飞华
- *date：2025/01/10 09:21:35
- */
-async fn query_btn_menu(id: &i64) -> Vec<String> {
-    let rb = &mut RB.clone();
-
-    let count = is_admin(&rb, id).await.unwrap_or_default();
-    let mut btn_menu: Vec<String> = Vec::new();
-    if count == 1 {
-        let data = Menu::select_all(rb).await;
-
-        for x in data.unwrap_or_default() {
-            btn_menu.push(x.api_url.unwrap_or_default());
-        }
-        log::info!("admin login: {:?}", id);
-        btn_menu
-    } else {
-        let btn_menu_map: Vec<HashMap<String, String>> = rb.query_decode("select distinct u.api_url from sys_user_role t left join sys_role usr on t.role_id = usr.id left join sys_role_menu srm on usr.id = srm.role_id left join sys_menu u on srm.menu_id = u.id where t.user_id = ?", vec![value!(id)]).await.unwrap();
-        for x in btn_menu_map {
-            btn_menu.push(x.get("api_url").unwrap().to_string());
-        }
-        log::info!("ordinary login: {:?}", id);
-        btn_menu
-    }
-}
-
 /*
  *查询用户角色
  *author：刘飞华
@@ -401,7 +1052,7 @@ pub async fn query_user_role(item: Json<QueryUserRoleReq>) -> AppResult<Response
     info!("query user_role params: {:?}", item);
     let rb = &mut RB.clone();
 
-    let role_list = Role::select_all(rb).await.map(|x| x.into_iter().map(|x| x.into()).collect::<Vec<RoleResp>>())?;
+    let role_list = Role::select_all_normal(rb).await.map(|x| x.into_iter().map(|x| x.into()).collect::<Vec<RoleResp>>())?;
     let sys_role_list = role_list.clone();
     let mut user_role_ids = role_list.into_iter().map(|x| x.id.unwrap_or_default()).collect::<Vec<i64>>();
 
@@ -421,7 +1072,6 @@ pub async fn query_user_role(item: Json<QueryUserRoleReq>) -> AppResult<Response
 #[web::post("/user/updateUserRole")]
 pub async fn update_user_role(item: Json<UpdateUserRoleReq>) -> AppResult<Response> {
     info!("update_user_role params: {:?}", item);
-    let rb = &mut RB.clone();
 
     let user_id = item.user_id;
     let role_ids = &item.role_ids;
@@ -431,8 +1081,6 @@ pub async fn update_user_role(item: Json<UpdateUserRoleReq>) -> AppResult<Respon
         return Err(AppError::BusinessError("不能修改超级管理员的角色"));
     }
 
-    UserRole::delete_by_map(rb, value! {"user_id": user_id}).await?;
-
     let mut list: Vec<UserRole> = Vec::new();
     for role_id in role_ids {
         let r_id = role_id.clone();
@@ -444,8 +1092,18 @@ pub async fn update_user_role(item: Json<UpdateUserRoleReq>) -> AppResult<Respon
         })
     }
 
-    UserRole::insert_batch(rb, &list, len as u64).await?;
+    in_transaction(|tx| {
+        Box::pin(async move {
+            UserRole::delete_by_map(tx, value! {"user_id": user_id}).await?;
+            if !list.is_empty() {
+                UserRole::insert_batch(tx, &list, len as u64).await?;
+            }
+            Ok(())
+        })
+    })
+    .await?;
 
+    invalidate_permissions();
     ok_result()
 }
 
@@ -478,7 +1136,7 @@ pub async fn query_user_menu(req: web::HttpRequest) -> AppResult<Response> {
                 sys_menu_list = Menu::select_all(rb).await?;
             } else {
                 log::info!("The current user is not a super administrator");
-                let sql_str = "select u.* from sys_user_role t left join sys_role usr on t.role_id = usr.id left join sys_role_menu srm on usr.id = srm.role_id left join sys_menu u on srm.menu_id = u.id where t.user_id = ?";
+                let sql_str = "select u.* from sys_user_role t join sys_role usr on t.role_id = usr.id left join sys_role_menu srm on usr.id = srm.role_id left join sys_menu u on srm.menu_id = u.id where t.user_id = ? and usr.del_flag = 1";
                 sys_menu_list = rb.query_decode(sql_str, vec![value!(user.id)]).await?;
             }
 
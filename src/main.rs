@@ -1,16 +1,23 @@
+// 中间件和服务嵌套较深,默认的递归深度不够编译器计算HttpServer的类型布局
+#![recursion_limit = "256"]
+
 #[macro_use]
 extern crate lazy_static;
 #[macro_use]
 extern crate rbatis;
 
-use std::env;
+use std::time::Duration;
 
+use crate::common::cli::{self, Command};
+use crate::common::config::APP_CONFIG;
+use crate::handler::other::{health_handler, metrics_handler};
 use crate::handler::system::{
-    sys_dept_handler, sys_dict_data_handler, sys_dict_type_handler, sys_login_log_handler, sys_menu_handler, sys_notice_handler, sys_operate_log_handler, sys_post_handler, sys_role_handler,
-    sys_user_handler,
+    sys_captcha_handler, sys_dept_handler, sys_dict_data_handler, sys_dict_type_handler, sys_login_log_handler, sys_menu_handler, sys_notice_handler, sys_online_handler, sys_operate_log_handler,
+    sys_post_handler, sys_recycle_handler, sys_role_handler, sys_user_handler,
 };
 use dotenvy::dotenv;
 use ntex::web;
+use ntex_cors::Cors;
 use rbatis::rbdc::pool::{ConnectionManager, Pool};
 use rbatis::RBatis;
 use rbdc_mysql::MysqlDriver;
@@ -29,89 +36,175 @@ lazy_static! {
 
 #[ntex::main]
 async fn main() -> std::io::Result<()> {
-    log4rs::init_file("src/config/log4rs.yaml", Default::default()).unwrap();
     dotenv().ok();
-    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set in .env file");
-    let manager = ConnectionManager::new(MysqlDriver {}, db_url.as_str()).expect("create connection manager error");
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(x) => x,
+        Err(msg) => {
+            eprintln!("{}\n\n{}", msg, cli::USAGE);
+            std::process::exit(2)
+        }
+    };
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        // 校验配置不需要初始化日志和数据库
+        Command::CheckConfig => exit_on_error(cli::check_config()),
+        _ => {}
+    }
+
+    lazy_static::initialize(&APP_CONFIG);
+    let config = &APP_CONFIG;
+    utils::mask_util::init_log(&config.log.config_file);
+    lazy_static::initialize(&utils::jwt_util::JWT_CONFIG);
+
+    let db = &config.database;
+    let manager = ConnectionManager::new(MysqlDriver {}, db.url.as_str()).expect("create connection manager error");
     let pool = FastPool::new(manager).expect("create db pool error");
+    pool.set_max_open_conns(db.max_open_conns).await;
+    pool.set_max_idle_conns(db.max_idle_conns).await;
+    pool.set_timeout(Some(Duration::from_secs(db.timeout_secs))).await;
+    pool.set_conn_max_lifetime((db.max_lifetime_secs > 0).then_some(Duration::from_secs(db.max_lifetime_secs))).await;
 
     RB.init_pool(pool).expect("init db pool error");
 
-    web::HttpServer::new(|| {
-        web::App::new().wrap(web::middleware::Logger::default()).wrap(middleware::auth::JwtAuth).service(
-            (web::scope("/api/system"))
-                .service(sys_user_handler::add_sys_user)
-                .service(sys_user_handler::delete_sys_user)
-                .service(sys_user_handler::update_sys_user)
-                .service(sys_user_handler::update_sys_user_status)
-                .service(sys_user_handler::update_sys_user_password)
-                .service(sys_user_handler::reset_sys_user_password)
-                .service(sys_user_handler::query_sys_user_detail)
-                .service(sys_user_handler::query_sys_user_list)
-                .service(sys_user_handler::query_user_role)
-                .service(sys_user_handler::update_user_role)
-                .service(sys_user_handler::query_user_menu)
-                .service(sys_user_handler::login)
-                .service(sys_role_handler::add_sys_role)
-                .service(sys_role_handler::delete_sys_role)
-                .service(sys_role_handler::update_sys_role)
-                .service(sys_role_handler::update_sys_role_status)
-                .service(sys_role_handler::query_sys_role_detail)
-                .service(sys_role_handler::query_sys_role_list)
-                .service(sys_role_handler::query_role_menu)
-                .service(sys_role_handler::update_role_menu)
-                .service(sys_role_handler::query_allocated_list)
-                .service(sys_role_handler::query_unallocated_list)
-                .service(sys_role_handler::cancel_auth_user)
-                .service(sys_role_handler::batch_cancel_auth_user)
-                .service(sys_role_handler::batch_auth_user)
-                .service(sys_menu_handler::add_sys_menu)
-                .service(sys_menu_handler::delete_sys_menu)
-                .service(sys_menu_handler::update_sys_menu)
-                .service(sys_menu_handler::update_sys_menu_status)
-                .service(sys_menu_handler::query_sys_menu_detail)
-                .service(sys_menu_handler::query_sys_menu_list)
-                .service(sys_menu_handler::query_sys_menu_list_simple)
-                .service(sys_menu_handler::query_sys_menu_resource_list)
-                .service(sys_post_handler::add_sys_post)
-                .service(sys_post_handler::delete_sys_post)
-                .service(sys_post_handler::update_sys_post)
-                .service(sys_post_handler::update_sys_post_status)
-                .service(sys_post_handler::query_sys_post_detail)
-                .service(sys_post_handler::query_sys_post_list)
-                .service(sys_operate_log_handler::delete_sys_operate_log)
-                .service(sys_operate_log_handler::query_sys_operate_log_detail)
-                .service(sys_operate_log_handler::query_sys_operate_log_list)
-                .service(sys_notice_handler::add_sys_notice)
-                .service(sys_notice_handler::delete_sys_notice)
-                .service(sys_notice_handler::update_sys_notice)
-                .service(sys_notice_handler::update_sys_notice_status)
-                .service(sys_notice_handler::query_sys_notice_detail)
-                .service(sys_notice_handler::query_sys_notice_list)
-                .service(sys_login_log_handler::delete_sys_login_log)
-                .service(sys_login_log_handler::query_sys_login_log_detail)
-                .service(sys_login_log_handler::query_sys_login_log_list)
-                .service(sys_dict_type_handler::add_sys_dict_type)
-                .service(sys_dict_type_handler::delete_sys_dict_type)
-                .service(sys_dict_type_handler::update_sys_dict_type)
-                .service(sys_dict_type_handler::update_sys_dict_type_status)
-                .service(sys_dict_type_handler::query_sys_dict_type_detail)
-                .service(sys_dict_type_handler::query_sys_dict_type_list)
-                .service(sys_dict_data_handler::add_sys_dict_data)
-                .service(sys_dict_data_handler::delete_sys_dict_data)
-                .service(sys_dict_data_handler::update_sys_dict_data)
-                .service(sys_dict_data_handler::update_sys_dict_data_status)
-                .service(sys_dict_data_handler::query_sys_dict_data_detail)
-                .service(sys_dict_data_handler::query_sys_dict_data_list)
-                .service(sys_dept_handler::add_sys_dept)
-                .service(sys_dept_handler::delete_sys_dept)
-                .service(sys_dept_handler::update_sys_dept)
-                .service(sys_dept_handler::update_sys_dept_status)
-                .service(sys_dept_handler::query_sys_dept_detail)
-                .service(sys_dept_handler::query_sys_dept_list),
-        )
-    })
-    .bind(("127.0.0.1", 8101))?
-    .run()
-    .await
+    // 除serve以外的子命令执行完成后退出
+    if command != Command::Serve {
+        exit_on_error(cli::run(command).await);
+    }
+
+    // 数据库版本高于程序时拒绝启动,避免旧程序操作新表结构
+    if let Err(err) = utils::migration_util::migrate_on_startup(&RB, db.auto_migrate).await {
+        log::error!("{}", err);
+        return Err(std::io::Error::other(err.to_string()));
+    }
+
+    let mut server = web::HttpServer::new(|| {
+        //跨域配置,allowed_origins为*时允许全部来源
+        let cors_config = &APP_CONFIG.cors;
+        let mut cors = Cors::new()
+            .allowed_methods(cors_config.allowed_methods.iter().map(|x| x.as_str()))
+            .allowed_headers(cors_config.allowed_headers.iter().map(|x| x.as_str()))
+            .max_age(cors_config.max_age_secs);
+        for origin in cors_config.allowed_origins.iter().filter(|x| x.as_str() != "*") {
+            cors = cors.allowed_origin(origin);
+        }
+
+        web::App::new()
+            .wrap(web::middleware::Logger::default())
+            .wrap(middleware::auth::JwtAuth)
+            .wrap(cors.finish())
+            .wrap(middleware::metrics::MetricsRecord)
+            .service(health_handler::health_live)
+            .service(health_handler::health_ready)
+            .service(health_handler::version)
+            .service(metrics_handler::metrics)
+            .service(
+                (web::scope(&APP_CONFIG.server.api_prefix))
+                    .wrap(middleware::operate_log::OperateLogRecord)
+                    .service(sys_user_handler::add_sys_user)
+                    .service(sys_user_handler::delete_sys_user)
+                    .service(sys_user_handler::update_sys_user)
+                    .service(sys_user_handler::update_sys_user_status)
+                    .service(sys_user_handler::update_sys_user_password)
+                    .service(sys_user_handler::change_expired_password)
+                    .service(sys_user_handler::forgot_password)
+                    .service(sys_user_handler::reset_password_by_token)
+                    .service(sys_user_handler::reset_sys_user_password)
+                    .service(sys_user_handler::unlock_sys_user)
+                    .service(sys_user_handler::setup_two_factor)
+                    .service(sys_user_handler::enable_two_factor)
+                    .service(sys_user_handler::verify_two_factor)
+                    .service(sys_user_handler::reset_two_factor)
+                    .service(sys_user_handler::query_sys_user_detail)
+                    .service(sys_user_handler::query_sys_user_list)
+                    .service(sys_user_handler::query_user_role)
+                    .service(sys_user_handler::update_user_role)
+                    .service(sys_user_handler::query_user_menu)
+                    .service(sys_user_handler::login)
+                    .service(sys_user_handler::refresh_token)
+                    .service(sys_user_handler::logout)
+                    .service(sys_role_handler::add_sys_role)
+                    .service(sys_role_handler::delete_sys_role)
+                    .service(sys_role_handler::update_sys_role)
+                    .service(sys_role_handler::update_sys_role_status)
+                    .service(sys_role_handler::query_sys_role_detail)
+                    .service(sys_role_handler::query_sys_role_list)
+                    .service(sys_role_handler::query_role_menu)
+                    .service(sys_role_handler::update_role_menu)
+                    .service(sys_role_handler::query_role_dept)
+                    .service(sys_role_handler::update_role_dept)
+                    .service(sys_role_handler::query_allocated_list)
+                    .service(sys_role_handler::query_unallocated_list)
+                    .service(sys_role_handler::cancel_auth_user)
+                    .service(sys_role_handler::batch_cancel_auth_user)
+                    .service(sys_role_handler::batch_auth_user)
+                    .service(sys_menu_handler::add_sys_menu)
+                    .service(sys_menu_handler::delete_sys_menu)
+                    .service(sys_menu_handler::update_sys_menu)
+                    .service(sys_menu_handler::update_sys_menu_status)
+                    .service(sys_menu_handler::query_sys_menu_detail)
+                    .service(sys_menu_handler::query_sys_menu_list)
+                    .service(sys_menu_handler::query_sys_menu_list_simple)
+                    .service(sys_menu_handler::query_sys_menu_resource_list)
+                    .service(sys_post_handler::add_sys_post)
+                    .service(sys_post_handler::delete_sys_post)
+                    .service(sys_post_handler::update_sys_post)
+                    .service(sys_post_handler::update_sys_post_status)
+                    .service(sys_post_handler::query_sys_post_detail)
+                    .service(sys_post_handler::query_sys_post_list)
+                    .service(sys_operate_log_handler::delete_sys_operate_log)
+                    .service(sys_operate_log_handler::query_sys_operate_log_detail)
+                    .service(sys_operate_log_handler::query_sys_operate_log_list)
+                    .service(sys_notice_handler::add_sys_notice)
+                    .service(sys_notice_handler::delete_sys_notice)
+                    .service(sys_notice_handler::update_sys_notice)
+                    .service(sys_notice_handler::update_sys_notice_status)
+                    .service(sys_notice_handler::query_sys_notice_detail)
+                    .service(sys_notice_handler::query_sys_notice_list)
+                    .service(sys_login_log_handler::delete_sys_login_log)
+                    .service(sys_login_log_handler::query_sys_login_log_detail)
+                    .service(sys_login_log_handler::query_sys_login_log_list)
+                    .service(sys_dict_type_handler::add_sys_dict_type)
+                    .service(sys_dict_type_handler::delete_sys_dict_type)
+                    .service(sys_dict_type_handler::update_sys_dict_type)
+                    .service(sys_dict_type_handler::update_sys_dict_type_status)
+                    .service(sys_dict_type_handler::query_sys_dict_type_detail)
+                    .service(sys_dict_type_handler::query_sys_dict_type_list)
+                    .service(sys_dict_data_handler::add_sys_dict_data)
+                    .service(sys_dict_data_handler::delete_sys_dict_data)
+                    .service(sys_dict_data_handler::update_sys_dict_data)
+                    .service(sys_dict_data_handler::update_sys_dict_data_status)
+                    .service(sys_dict_data_handler::query_sys_dict_data_detail)
+                    .service(sys_dict_data_handler::query_sys_dict_data_list)
+                    .service(sys_dept_handler::add_sys_dept)
+                    .service(sys_dept_handler::delete_sys_dept)
+                    .service(sys_dept_handler::update_sys_dept)
+                    .service(sys_dept_handler::update_sys_dept_status)
+                    .service(sys_dept_handler::query_sys_dept_detail)
+                    .service(sys_dept_handler::query_sys_dept_list)
+                    .service(sys_online_handler::query_online_list)
+                    .service(sys_online_handler::force_logout)
+                    .service(sys_recycle_handler::query_recycle_list)
+                    .service(sys_recycle_handler::restore_recycle)
+                    .service(sys_recycle_handler::purge_recycle)
+                    .service(sys_captcha_handler::captcha_image),
+            )
+    });
+    if config.server.workers > 0 {
+        server = server.workers(config.server.workers);
+    }
+    server.bind((config.server.host.as_str(), config.server.port))?.run().await
+}
+
+// 子命令执行完成后退出,失败时输出错误信息并返回非0退出码
+fn exit_on_error(result: common::error::AppResult<()>) -> ! {
+    match result {
+        Ok(_) => std::process::exit(0),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1)
+        }
+    }
 }
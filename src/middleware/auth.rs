@@ -1,11 +1,50 @@
+use crate::common::config::APP_CONFIG;
 use crate::common::error::AppError;
 use crate::common::result::BaseResponse;
-use crate::utils::jwt_util::JwtToken;
+use crate::utils::ip_util::client_ip;
+use crate::utils::jwt_util::{JwtToken, JWT_CONFIG};
+use crate::utils::metrics_util::{record_auth, AuthResult};
+use crate::utils::path_pattern_util::check_permission;
+use crate::utils::permission_util::{get_permissions, permission_version};
+use crate::utils::revoke_util::is_revoked;
+use crate::utils::session_util::touch_session;
 use log::info;
 use ntex::http::header;
 use ntex::service::{Middleware, Service, ServiceCtx};
 use ntex::web;
 use ntex::web::HttpResponse;
+use std::collections::HashSet;
+
+// 不需要登录就可以访问的接口(系统管理接口,不含接口前缀)
+const PUBLIC_API_PATHS: &[&str] = &[
+    "/user/login",
+    "/user/refreshToken",
+    "/user/2fa/verify",
+    "/user/changeExpiredPassword",
+    "/user/forgotPassword",
+    "/user/resetPasswordByToken",
+    "/captcha/image",
+];
+
+// 不需要登录就可以访问的接口(健康检查、版本信息和Prometheus指标)
+const PUBLIC_ROOT_PATHS: &[&str] = &["/health/live", "/health/ready", "/version", "/metrics"];
+
+lazy_static! {
+    static ref PUBLIC_PATHS: HashSet<String> = PUBLIC_API_PATHS
+        .iter()
+        .map(|x| format!("{}{}", APP_CONFIG.server.api_prefix, x))
+        .chain(PUBLIC_ROOT_PATHS.iter().map(|x| x.to_string()))
+        .collect();
+}
+
+/*
+ *是否是不需要登录的接口(完整路径匹配)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+fn is_public_path(path: &str) -> bool {
+    PUBLIC_PATHS.contains(path)
+}
 
 // There are two steps in middleware processing.
 // 1. Middleware initialization, middleware factory gets called with
@@ -41,11 +80,12 @@ where
         let header_value = header::HeaderValue::from_str("").unwrap();
         let authorization = req.headers().get("Authorization").unwrap_or(&header_value).to_str().unwrap().to_string();
 
-        if path.contains("login") {
+        if is_public_path(&path) {
             return Ok(ctx.clone().call(&self.service, req).await?);
         }
 
         if authorization.len() <= 0 {
+            record_auth(AuthResult::InvalidToken);
             let res: BaseResponse<String> = BaseResponse {
                 code: 1,
                 msg: "token不能为空".to_string(),
@@ -54,7 +94,7 @@ where
             return Ok(req.into_response(HttpResponse::Ok().json(&res)));
         }
 
-        let jwt_token = match JwtToken::verify("123", &authorization.replace("Bearer ", "")) {
+        let jwt_token = match JwtToken::verify(&JWT_CONFIG, &authorization.replace("Bearer ", "")) {
             Ok(j_token) => j_token,
             Result::Err(err) => {
                 let er = match err {
@@ -62,16 +102,51 @@ where
                     _ => "no math error".to_string(),
                 };
                 log::error!("You requested path: {}, token is err: {}", path, er);
+                record_auth(AuthResult::InvalidToken);
                 let res: BaseResponse<String> = BaseResponse { code: 1, msg: er, data: None };
                 return Ok(req.into_response(HttpResponse::Ok().json(&res)));
             }
         };
 
-        if jwt_token.permissions.contains(&path) {
+        if is_revoked(&jwt_token) {
+            log::error!("You requested path: {}, token is revoked, user_id: {}", path, jwt_token.id);
+            record_auth(AuthResult::InvalidToken);
+            let res: BaseResponse<String> = BaseResponse {
+                code: 1,
+                msg: "token已失效,请重新登录".to_string(),
+                data: None,
+            };
+            return Ok(req.into_response(HttpResponse::Ok().json(&res)));
+        }
+
+        let permissions = match get_permissions(jwt_token.id).await {
+            Ok(x) => x,
+            Err(err) => {
+                log::error!("You requested path: {}, load permissions error: {}", path, err);
+                record_auth(AuthResult::Error);
+                let res: BaseResponse<String> = BaseResponse {
+                    code: 1,
+                    msg: "查询权限失败".to_string(),
+                    data: None,
+                };
+                return Ok(req.into_response(HttpResponse::Ok().json(&res)));
+            }
+        };
+
+        if check_permission(&permissions, req.method().as_str(), &path) {
+            record_auth(AuthResult::Allowed);
+            touch_session(&jwt_token.sid, &client_ip(req.peer_addr(), req.headers()));
             req.headers_mut().insert("userId".parse().unwrap(), jwt_token.id.to_string().parse().unwrap());
-            Ok(ctx.call(&self.service, req).await?)
+            let mut res = ctx.call(&self.service, req).await?;
+            //令牌签发后权限发生过变更,通知前端重新查询菜单
+            let version = permission_version();
+            if jwt_token.pv != version {
+                res.headers_mut().insert("X-Permission-Version".parse().unwrap(), version.to_string().parse().unwrap());
+            }
+            Ok(res)
         } else {
             log::error!("You has no permissions requested path: {:?}", &path);
+            record_auth(AuthResult::Denied);
             let res: BaseResponse<String> = BaseResponse {
                 code: 1,
                 msg: "无权限访问".to_string(),
@@ -0,0 +1,143 @@
+use crate::common::config::APP_CONFIG;
+use crate::utils::metrics_util::record_request;
+use ntex::http::StatusCode;
+use ntex::service::{Middleware, Service, ServiceCtx};
+use ntex::web;
+use std::collections::HashSet;
+use std::time::Instant;
+
+// 不在系统管理接口前缀下的接口
+const ROOT_ROUTES: &[&str] = &["/health/live", "/health/ready", "/version", "/metrics"];
+
+// 系统管理接口(不含接口前缀),需要和main.rs中注册的接口一致
+#[rustfmt::skip]
+const API_ROUTES: &[&str] = &[
+    "/user/addUser", "/user/deleteUser", "/user/updateUser", "/user/updateUserStatus", "/user/updateUserPassword",
+    "/user/changeExpiredPassword", "/user/forgotPassword", "/user/resetPasswordByToken", "/user/resetUserPassword",
+    "/user/unlockUser", "/user/2fa/setup", "/user/2fa/enable", "/user/2fa/verify", "/user/2fa/reset",
+    "/user/queryUserDetail", "/user/queryUserList", "/user/queryUserRole", "/user/updateUserRole", "/user/queryUserMenu",
+    "/user/login", "/user/refreshToken", "/user/logout",
+    "/role/addRole", "/role/deleteRole", "/role/updateRole", "/role/updateRoleStatus", "/role/queryRoleDetail",
+    "/role/queryRoleList", "/role/queryRoleMenu", "/role/updateRoleMenu", "/role/queryRoleDept", "/role/updateRoleDept",
+    "/role/queryAllocatedList", "/role/queryUnallocatedList", "/role/cancelAuthUser", "/role/batchCancelAuthUser",
+    "/role/batchAuthUser",
+    "/menu/addMenu", "/menu/deleteMenu", "/menu/updateMenu", "/menu/updateMenuStatus", "/menu/queryMenuDetail",
+    "/menu/queryMenuList", "/menu/queryMenuListSimple", "/menu/queryMenuResourceList",
+    "/post/addPost", "/post/deletePost", "/post/updatePost", "/post/updatePostStatus", "/post/queryPostDetail",
+    "/post/queryPostList",
+    "/operateLog/deleteOperateLog", "/operateLog/queryOperateLogDetail", "/operateLog/queryOperateLogList",
+    "/notice/addNotice", "/notice/deleteNotice", "/notice/updateNotice", "/notice/updateNoticeStatus",
+    "/notice/queryNoticeDetail", "/notice/queryNoticeList",
+    "/loginLog/deleteLoginLog", "/loginLog/queryLoginLogDetail", "/loginLog/queryLoginLogList",
+    "/dictType/addDictType", "/dictType/deleteDictType", "/dictType/updateDictType", "/dictType/updateDictTypeStatus",
+    "/dictType/queryDictTypeDetail", "/dictType/queryDictTypeList",
+    "/dictData/addDictData", "/dictData/deleteDictData", "/dictData/updateDictData", "/dictData/updateDictDataStatus",
+    "/dictData/queryDictDataDetail", "/dictData/queryDictDataList",
+    "/dept/addDept", "/dept/deleteDept", "/dept/updateDept", "/dept/updateDeptStatus", "/dept/queryDeptDetail",
+    "/dept/queryDeptList",
+    "/online/queryOnlineList", "/online/forceLogout",
+    "/recycle/queryList", "/recycle/restore", "/recycle/purge",
+    "/captcha/image",
+];
+
+lazy_static! {
+    static ref ROUTES: HashSet<String> = API_ROUTES
+        .iter()
+        .map(|x| format!("{}{}", APP_CONFIG.server.api_prefix, x))
+        .chain(ROOT_ROUTES.iter().map(|x| x.to_string()))
+        .collect();
+}
+
+/*
+ *请求对应的route标签(注册的接口路径,其它请求统一记为unmatched,避免标签数量无限增长)
+ *在鉴权之前根据路径判断,未登录的请求也不会产生新的标签
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+fn route_label(path: &str) -> &str {
+    if ROUTES.contains(path) {
+        path
+    } else {
+        "unmatched"
+    }
+}
+
+// 请求指标中间件,注册在最外层,鉴权失败、跨域预检等请求也会被统计
+pub struct MetricsRecord;
+
+impl<S> Middleware<S> for MetricsRecord {
+    type Service = MetricsMiddleware<S>;
+
+    fn create(&self, service: S) -> Self::Service {
+        MetricsMiddleware { service }
+    }
+}
+
+pub struct MetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, Err> Service<web::WebRequest<Err>> for MetricsMiddleware<S>
+where
+    S: Service<web::WebRequest<Err>, Response = web::WebResponse, Error = web::Error>,
+    Err: web::ErrorRenderer,
+{
+    type Response = web::WebResponse;
+    type Error = web::Error;
+
+    ntex::forward_ready!(service);
+
+    async fn call(&self, req: web::WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
+        let start = Instant::now();
+        let method = req.method().to_string();
+        let route = route_label(req.path()).to_string();
+
+        let result = ctx.call(&self.service, req).await;
+        let status = match &result {
+            Ok(res) => res.status(),
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        record_request(&method, &route, status.as_u16(), start.elapsed().as_secs_f64());
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::middleware::metrics::{route_label, API_ROUTES, ROOT_ROUTES};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_route_label() {
+        assert_eq!(route_label("/api/system/user/login"), "/api/system/user/login");
+        assert_eq!(route_label("/metrics"), "/metrics");
+        assert_eq!(route_label("/api/system/user/login/1"), "unmatched");
+        assert_eq!(route_label("/api/system/random/abc"), "unmatched");
+    }
+
+    // 接口列表需要和main.rs中注册的接口一致(根据接口函数上的路由注解查找路径)
+    #[test]
+    fn test_routes_registered() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let main = std::fs::read_to_string(format!("{}/src/main.rs", dir)).unwrap();
+
+        let mut registered = HashSet::new();
+        for service in main.lines().filter_map(|x| x.trim().strip_prefix(".service(")) {
+            let Some((module, handler)) = service.trim_end_matches(&[')', ','][..]).split_once("::") else {
+                continue;
+            };
+            let folder = if module.starts_with("sys_") { "system" } else { "other" };
+            let source = std::fs::read_to_string(format!("{}/src/handler/{}/{}.rs", dir, folder, module)).unwrap();
+            let end = source.find(&format!("pub async fn {}(", handler)).unwrap();
+            let attr = source[..end].rfind("#[web::").unwrap();
+            let path = source[attr..end].split('"').nth(1).unwrap();
+            registered.insert(path.to_string());
+        }
+
+        let routes = API_ROUTES.iter().chain(ROOT_ROUTES.iter()).map(|x| x.to_string()).collect::<HashSet<String>>();
+        assert_eq!(routes.len(), API_ROUTES.len() + ROOT_ROUTES.len());
+        assert_eq!(routes, registered);
+    }
+}
@@ -1 +1,3 @@
 pub mod auth;
+pub mod metrics;
+pub mod operate_log;
@@ -0,0 +1,288 @@
+use crate::model::system::sys_dept_model::Dept;
+use crate::model::system::sys_operate_log_model::OperateLog;
+use crate::model::system::sys_user_model::User;
+use crate::utils::ip_region_util::ip_location;
+use crate::utils::ip_util::client_ip;
+use crate::utils::mask_util::MASKER;
+use crate::RB;
+use futures::stream::{self, StreamExt};
+use ntex::http::body::{Body, ResponseBody};
+use ntex::http::{Method, Payload};
+use ntex::service::{Middleware, Service, ServiceCtx};
+use ntex::util::{Bytes, BytesMut};
+use ntex::web;
+use rbatis::rbdc::DateTime;
+use std::time::Instant;
+
+// 参数、返回结果和错误消息的最大保存长度(对应表字段长度)
+const MAX_LEN: usize = 2000;
+
+/*
+ *接口的模块标题和业务类型(没有配置的POST接口按其它记录)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+struct OperateRoute {
+    url: &'static str,    //请求URL(不含/api/system前缀)
+    title: &'static str,  //模块标题
+    business_type: i8,    //业务类型（0其它 1新增 2修改 3删除）
+    method: &'static str, //方法名称
+    record_result: bool,  //是否记录返回结果(返回密钥、恢复码等敏感信息的接口不记录)
+}
+
+const fn route(url: &'static str, title: &'static str, business_type: i8, method: &'static str) -> OperateRoute {
+    OperateRoute {
+        url,
+        title,
+        business_type,
+        method,
+        record_result: true,
+    }
+}
+
+// 返回结果包含敏感信息,不记录返回结果
+const fn secret_route(url: &'static str, title: &'static str, business_type: i8, method: &'static str) -> OperateRoute {
+    OperateRoute {
+        url,
+        title,
+        business_type,
+        method,
+        record_result: false,
+    }
+}
+
+#[rustfmt::skip]
+const OPERATE_ROUTES: &[OperateRoute] = &[
+    route("/user/addUser", "用户管理", 1, "sys_user_handler::add_sys_user"),
+    route("/user/deleteUser", "用户管理", 3, "sys_user_handler::delete_sys_user"),
+    route("/user/updateUser", "用户管理", 2, "sys_user_handler::update_sys_user"),
+    route("/user/updateUserStatus", "用户管理", 2, "sys_user_handler::update_sys_user_status"),
+    route("/user/resetUserPassword", "用户管理", 2, "sys_user_handler::reset_sys_user_password"),
+    route("/user/updateUserPassword", "用户管理", 2, "sys_user_handler::update_sys_user_password"),
+    route("/user/updateUserRole", "用户管理", 2, "sys_user_handler::update_user_role"),
+    route("/user/unlockUser", "用户管理", 2, "sys_user_handler::unlock_sys_user"),
+    secret_route("/user/2fa/setup", "用户管理", 0, "sys_user_handler::setup_two_factor"),
+    secret_route("/user/2fa/enable", "用户管理", 2, "sys_user_handler::enable_two_factor"),
+    route("/user/2fa/reset", "用户管理", 2, "sys_user_handler::reset_two_factor"),
+    route("/user/changeExpiredPassword", "用户管理", 2, "sys_user_handler::change_expired_password"),
+    route("/user/forgotPassword", "用户管理", 0, "sys_user_handler::forgot_password"),
+    route("/user/resetPasswordByToken", "用户管理", 2, "sys_user_handler::reset_password_by_token"),
+    route("/user/login", "用户登录", 0, "sys_user_handler::login"),
+    secret_route("/user/2fa/verify", "用户登录", 0, "sys_user_handler::verify_two_factor"),
+    route("/user/refreshToken", "用户登录", 0, "sys_user_handler::refresh_token"),
+    route("/user/logout", "用户登录", 0, "sys_user_handler::logout"),
+    route("/role/addRole", "角色管理", 1, "sys_role_handler::add_sys_role"),
+    route("/role/deleteRole", "角色管理", 3, "sys_role_handler::delete_sys_role"),
+    route("/role/updateRole", "角色管理", 2, "sys_role_handler::update_sys_role"),
+    route("/role/updateRoleStatus", "角色管理", 2, "sys_role_handler::update_sys_role_status"),
+    route("/role/updateRoleMenu", "角色管理", 2, "sys_role_handler::update_role_menu"),
+    route("/role/updateRoleDept", "角色管理", 2, "sys_role_handler::update_role_dept"),
+    route("/role/cancelAuthUser", "角色管理", 2, "sys_role_handler::cancel_auth_user"),
+    route("/role/batchCancelAuthUser", "角色管理", 2, "sys_role_handler::batch_cancel_auth_user"),
+    route("/role/batchAuthUser", "角色管理", 2, "sys_role_handler::batch_auth_user"),
+    route("/menu/addMenu", "菜单管理", 1, "sys_menu_handler::add_sys_menu"),
+    route("/menu/deleteMenu", "菜单管理", 3, "sys_menu_handler::delete_sys_menu"),
+    route("/menu/updateMenu", "菜单管理", 2, "sys_menu_handler::update_sys_menu"),
+    route("/menu/updateMenuStatus", "菜单管理", 2, "sys_menu_handler::update_sys_menu_status"),
+    route("/post/addPost", "岗位管理", 1, "sys_post_handler::add_sys_post"),
+    route("/post/deletePost", "岗位管理", 3, "sys_post_handler::delete_sys_post"),
+    route("/post/updatePost", "岗位管理", 2, "sys_post_handler::update_sys_post"),
+    route("/post/updatePostStatus", "岗位管理", 2, "sys_post_handler::update_sys_post_status"),
+    route("/dept/addDept", "部门管理", 1, "sys_dept_handler::add_sys_dept"),
+    route("/dept/deleteDept", "部门管理", 3, "sys_dept_handler::delete_sys_dept"),
+    route("/dept/updateDept", "部门管理", 2, "sys_dept_handler::update_sys_dept"),
+    route("/dept/updateDeptStatus", "部门管理", 2, "sys_dept_handler::update_sys_dept_status"),
+    route("/dictType/addDictType", "字典类型", 1, "sys_dict_type_handler::add_sys_dict_type"),
+    route("/dictType/deleteDictType", "字典类型", 3, "sys_dict_type_handler::delete_sys_dict_type"),
+    route("/dictType/updateDictType", "字典类型", 2, "sys_dict_type_handler::update_sys_dict_type"),
+    route("/dictType/updateDictTypeStatus", "字典类型", 2, "sys_dict_type_handler::update_sys_dict_type_status"),
+    route("/dictData/addDictData", "字典数据", 1, "sys_dict_data_handler::add_sys_dict_data"),
+    route("/dictData/deleteDictData", "字典数据", 3, "sys_dict_data_handler::delete_sys_dict_data"),
+    route("/dictData/updateDictData", "字典数据", 2, "sys_dict_data_handler::update_sys_dict_data"),
+    route("/dictData/updateDictDataStatus", "字典数据", 2, "sys_dict_data_handler::update_sys_dict_data_status"),
+    route("/notice/addNotice", "通知公告", 1, "sys_notice_handler::add_sys_notice"),
+    route("/notice/deleteNotice", "通知公告", 3, "sys_notice_handler::delete_sys_notice"),
+    route("/notice/updateNotice", "通知公告", 2, "sys_notice_handler::update_sys_notice"),
+    route("/notice/updateNoticeStatus", "通知公告", 2, "sys_notice_handler::update_sys_notice_status"),
+    route("/operateLog/deleteOperateLog", "操作日志", 3, "sys_operate_log_handler::delete_sys_operate_log"),
+    route("/operateLog/cleanOperateLog", "操作日志", 3, "sys_operate_log_handler::clean_sys_operate_log"),
+    route("/loginLog/deleteLoginLog", "登录日志", 3, "sys_login_log_handler::delete_sys_login_log"),
+    route("/loginLog/cleanLoginLog", "登录日志", 3, "sys_login_log_handler::clean_sys_login_log"),
+    route("/online/forceLogout", "在线用户", 0, "sys_online_handler::force_logout"),
+    route("/recycle/restore", "回收站", 2, "sys_recycle_handler::restore_recycle"),
+    route("/recycle/purge", "回收站", 3, "sys_recycle_handler::purge_recycle"),
+];
+
+// 不记录操作日志的只读接口(query开头的查询接口除外)
+const READONLY_ROUTES: &[&str] = &["/captcha/image"];
+
+fn find_route(path: &str) -> Option<&'static OperateRoute> {
+    OPERATE_ROUTES.iter().find(|x| path.ends_with(x.url))
+}
+
+/*
+ *是否需要记录操作日志(记录除查询接口以外的所有POST请求)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+fn need_record(method: &Method, path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    method == Method::POST && !name.starts_with("query") && !READONLY_ROUTES.iter().any(|x| path.ends_with(x))
+}
+
+fn truncate(s: &str) -> String {
+    match s.char_indices().nth(MAX_LEN) {
+        None => s.to_string(),
+        Some((i, _)) => s[..i].to_string(),
+    }
+}
+
+// 操作日志中间件,需要在JwtAuth之后执行(依赖JwtAuth设置的userId请求头),所以注册在/api/system作用域上
+pub struct OperateLogRecord;
+
+impl<S> Middleware<S> for OperateLogRecord {
+    type Service = OperateLogMiddleware<S>;
+
+    fn create(&self, service: S) -> Self::Service {
+        OperateLogMiddleware { service }
+    }
+}
+
+pub struct OperateLogMiddleware<S> {
+    service: S,
+}
+
+impl<S, Err> Service<web::WebRequest<Err>> for OperateLogMiddleware<S>
+where
+    S: Service<web::WebRequest<Err>, Response = web::WebResponse, Error = web::Error>,
+    Err: web::ErrorRenderer,
+{
+    type Response = web::WebResponse;
+    type Error = web::Error;
+
+    ntex::forward_ready!(service);
+
+    async fn call(&self, mut req: web::WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
+        if !need_record(req.method(), req.path()) {
+            return ctx.call(&self.service, req).await;
+        }
+        let route = find_route(req.path());
+
+        let start = Instant::now();
+        let mut log = OperateLog {
+            id: None,
+            title: Some(route.map_or("其它", |x| x.title).to_string()),
+            business_type: Some(route.map_or(0, |x| x.business_type)),
+            method: route.map(|x| x.method.to_string()),
+            request_method: Some(req.method().to_string()),
+            operator_type: Some(1),
+            operate_name: None,
+            dept_name: None,
+            operate_url: Some(req.path().to_string()),
+            operate_ip: Some(client_ip(req.peer_addr(), req.headers())),
+            operate_location: None,
+            operate_param: None,
+            json_result: None,
+            status: Some(1),
+            error_msg: None,
+            operate_time: Some(DateTime::now()),
+            cost_time: None,
+        };
+        let user_id = req.headers().get("userId").and_then(|x| x.to_str().ok()).and_then(|x| x.parse::<i64>().ok());
+
+        // 只读取请求体的前MAX_LEN个字节用于记录,读取的部分和剩余的请求体重新放回,保证后续的Json提取器可以正常读取
+        let mut payload = req.take_payload();
+        let mut body = BytesMut::new();
+        while body.len() < MAX_LEN {
+            match payload.recv().await {
+                None => break,
+                Some(chunk) => body.extend_from_slice(&chunk?),
+            }
+        }
+        let body: Bytes = body.freeze();
+        log.operate_param = Some(truncate(&MASKER.mask(&String::from_utf8_lossy(&body[..body.len().min(MAX_LEN)]))));
+
+        req.set_payload(Payload::from_stream(stream::iter(vec![Ok(body)]).chain(payload)));
+
+        let result = ctx.call(&self.service, req).await;
+        log.cost_time = Some(start.elapsed().as_millis() as i64);
+
+        match &result {
+            Ok(res) => {
+                let json_result = match res.response().body() {
+                    ResponseBody::Body(Body::Bytes(b)) | ResponseBody::Other(Body::Bytes(b)) => String::from_utf8_lossy(b).to_string(),
+                    _ => String::new(),
+                };
+                // 业务异常也是以200返回的,需要根据返回的code判断
+                if let Ok(value) = serde_json::from_str::<serde_json::Value>(&json_result) {
+                    if value["code"].as_i64().unwrap_or_default() != 0 {
+                        log.status = Some(0);
+                        log.error_msg = value["msg"].as_str().map(truncate);
+                    }
+                }
+                if !res.status().is_success() {
+                    log.status = Some(0);
+                }
+                if route.is_none_or(|x| x.record_result) {
+                    log.json_result = Some(truncate(&MASKER.mask(&json_result)));
+                }
+            }
+            Err(err) => {
+                log.status = Some(0);
+                log.error_msg = Some(truncate(&err.to_string()));
+            }
+        }
+
+        ntex::rt::spawn(save_operate_log(log, user_id));
+
+        result
+    }
+}
+
+/*
+ *保存操作日志(查询操作人员、部门名称和操作地点)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+async fn save_operate_log(mut log: OperateLog, user_id: Option<i64>) {
+    let rb = &mut RB.clone();
+    log.operate_location = log.operate_ip.as_deref().map(ip_location);
+
+    if let Some(user_id) = user_id {
+        match User::select_by_id(rb, user_id).await {
+            Ok(Some(user)) => {
+                log.operate_name = Some(user.user_name);
+                if let Ok(Some(dept)) = Dept::select_by_id(rb, &user.dept_id).await {
+                    log.dept_name = Some(dept.dept_name);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => log::error!("query operate user error: {}", err),
+        }
+    }
+
+    if let Err(err) = OperateLog::insert(rb, &log).await {
+        log::error!("save operate log error: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::middleware::operate_log::{find_route, need_record};
+    use ntex::http::Method;
+
+    #[test]
+    fn test_need_record() {
+        assert!(need_record(&Method::POST, "/api/system/user/addUser"));
+        assert!(need_record(&Method::POST, "/api/system/user/logout"));
+        assert!(need_record(&Method::POST, "/api/system/other/newAction"));
+        assert!(!need_record(&Method::POST, "/api/system/user/queryUserList"));
+        assert!(!need_record(&Method::POST, "/api/system/captcha/image"));
+        assert!(!need_record(&Method::GET, "/api/system/user/queryUserMenu"));
+
+        assert_eq!(find_route("/api/system/user/2fa/setup").map(|x| x.business_type), Some(0));
+        assert_eq!(find_route("/api/system/user/2fa/verify").map(|x| x.record_result), Some(false));
+        assert_eq!(find_route("/api/system/user/addUser").map(|x| x.record_result), Some(true));
+        assert!(find_route("/api/system/other/newAction").is_none());
+    }
+}
@@ -6,9 +6,15 @@ pub mod sys_menu_model;
 pub mod sys_notice_model;
 pub mod sys_operate_log_model;
 pub mod sys_post_model;
+pub mod sys_recycle_model;
 pub mod sys_role_dept_model;
 pub mod sys_role_menu_model;
 pub mod sys_role_model;
+pub mod sys_schema_migration_model;
+pub mod sys_user_login_fail_model;
+pub mod sys_user_mfa_model;
 pub mod sys_user_model;
+pub mod sys_user_password_history_model;
 pub mod sys_user_post_model;
 pub mod sys_user_role_model;
+pub mod sys_user_token_model;
@@ -2,9 +2,13 @@
 // author：刘飞华
 // createTime：2024/12/25 10:01:11
 
+use crate::rbatis::rbatis_codegen::IntoSql;
 use crate::vo::system::sys_dept_vo::{DeptReq, DeptResp};
+use rbatis::executor::Executor;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
 use rbatis::RBatis;
+use rbs::value;
 use serde::{Deserialize, Serialize};
 /*
  *部门
@@ -85,14 +89,14 @@ impl Into<DeptResp> for Dept {
  *author：刘飞华
  *date：2024/12/25 10:01:11
  */
-impl_select!(Dept{select_by_id(id:&i64) -> Option => "`where id = #{id} limit 1`"}, "sys_dept");
+impl_select!(Dept{select_by_id(id:&i64) -> Option => "`where id = #{id} and del_flag = 1 limit 1`"}, "sys_dept");
 
 /*
  *根据部门名称查询部门
  *author：刘飞华
  *date：2024/12/25 10:01:11
  */
-impl_select!(Dept{select_by_dept_name(dept_name:&str, parent_id:i64) -> Option => "`where dept_name = #{dept_name} and parent_id = #{parent_id} limit 1`"}, "sys_dept");
+impl_select!(Dept{select_by_dept_name(dept_name:&str, parent_id:i64) -> Option => "`where dept_name = #{dept_name} and parent_id = #{parent_id} and del_flag = 1 limit 1`"}, "sys_dept");
 
 /*
  *分页查询部门
@@ -100,6 +104,7 @@ impl_select!(Dept{select_by_dept_name(dept_name:&str, parent_id:i64) -> Option =
  *date：2024/12/25 10:01:11
  */
 impl_select_page!(Dept{select_page() =>"
+     ` where del_flag = 1 `
      if !sql.contains('count'):
        order by create_time desc"
 },"sys_dept");
@@ -109,12 +114,14 @@ impl_select_page!(Dept{select_page() =>"
  *author：刘飞华
  *date：2024/12/25 10:01:11
  */
-impl_select!(Dept{select_page_dept_list(dept_name:&str, status:i8) =>"
-    where 1=1
+impl_select!(Dept{select_page_dept_list(dept_name:&str, status:i8, all_data:bool, dept_ids:&[i64]) =>"
+    where del_flag = 1
      if dept_name != null && dept_name != '':
       ` and dept_name = #{dept_name} `
      if status != 2:
       ` and status = #{status} `
+     if !all_data:
+      ` and id in ${dept_ids.sql()} `
      if !sql.contains('count'):
       ` order by sort"
 },"sys_dept");
@@ -154,7 +161,16 @@ pub async fn check_dept_exist_user(rb: &RBatis, id: &i64) -> rbatis::Result<i64>
  * author：刘飞华
  * date：2025/1/6 11:29
  */
-#[sql("select * from sys_dept where find_in_set(?, ancestors)")]
 pub async fn select_children_dept_by_id(rb: &RBatis, id: &i64) -> rbatis::Result<Vec<Dept>> {
+    rb.query_decode("select * from sys_dept where del_flag = 1 and find_in_set(?, ancestors)", vec![value!(id)]).await
+}
+
+/*
+ *逻辑删除部门(del_flag设置为0,数据进入回收站)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[py_sql("`update sys_dept set del_flag = 0, update_time = now() where del_flag = 1 and id in ${ids.sql()}`")]
+pub async fn soft_delete_dept_by_ids(rb: &dyn Executor, ids: &[i64]) -> rbatis::Result<ExecResult> {
     impled!()
 }
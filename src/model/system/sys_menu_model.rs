@@ -5,7 +5,9 @@ use crate::rbatis::rbatis_codegen::IntoSql;
 use crate::vo::system::sys_menu_vo::{MenuReq, MenuResp};
 use rbatis::rbdc::datetime::DateTime;
 use rbatis::RBatis;
+use rbs::value;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /*
  *菜单信息
@@ -120,6 +122,17 @@ pub async fn select_count_menu_by_parent_id(rb: &RBatis, parent_id: &i64) -> rba
     impled!()
 }
 
+/*
+ *根据用户id查询接口权限(只包括启用的角色和菜单)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn select_api_url_by_user_id(rb: &RBatis, user_id: &i64) -> rbatis::Result<Vec<HashMap<String, String>>> {
+    let sql = "select distinct u.api_url from sys_user_role t join sys_role r on t.role_id = r.id join sys_role_menu srm on t.role_id = srm.role_id join sys_menu u on srm.menu_id = u.id \
+               where t.user_id = ? and r.del_flag = 1 and r.status = 1 and u.status = 1 and u.api_url != ''";
+    rb.query_decode(sql, vec![value!(user_id)]).await
+}
+
 /*
  *查询菜单信息(排除按钮)
  *author：刘飞华
@@ -0,0 +1,80 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use crate::vo::system::sys_recycle_vo::RecycleResp;
+use rbatis::executor::Executor;
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+/*
+ *回收站数据(逻辑删除的用户、角色、部门)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecycleItem {
+    pub recycle_type: String,          //数据类型(user:用户,role:角色,dept:部门)
+    pub id: i64,                       //主键
+    pub name: String,                  //名称(用户账号、角色名称、部门名称)
+    pub delete_time: Option<DateTime>, //删除时间(逻辑删除时更新的update_time)
+}
+
+impl From<RecycleItem> for RecycleResp {
+    fn from(item: RecycleItem) -> Self {
+        RecycleResp {
+            recycle_type: item.recycle_type, //数据类型
+            id: item.id,                     //主键
+            name: item.name,                 //名称
+            delete_time: item.delete_time,   //删除时间
+        }
+    }
+}
+
+/*
+ *根据条件分页查询回收站数据
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[py_sql(
+    "`select * from (`
+      `select 'user' as recycle_type, id, user_name as name, update_time as delete_time from sys_user where del_flag = 0`
+      ` union all select 'role' as recycle_type, id, role_name as name, update_time as delete_time from sys_role where del_flag = 0`
+      ` union all select 'dept' as recycle_type, id, dept_name as name, update_time as delete_time from sys_dept where del_flag = 0`
+      `) t where 1 = 1`
+            if recycle_type != '':
+                ` and t.recycle_type = #{recycle_type} `
+            if name != '':
+                ` and t.name like concat('%', #{name}, '%') `
+            if begin_time != '':
+                ` and t.delete_time >= #{begin_time} `
+            if end_time != '':
+                ` and t.delete_time <= #{end_time} `
+            ` order by t.delete_time desc limit #{page_no},#{page_size}` "
+)]
+pub async fn select_recycle_list(rb: &dyn Executor, recycle_type: &str, name: &str, begin_time: &str, end_time: &str, page_no: u64, page_size: u64) -> rbatis::Result<Vec<RecycleItem>> {
+    impled!()
+}
+
+/*
+ *根据条件查询回收站数据数量
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[py_sql(
+    "`select count(1) from (`
+      `select 'user' as recycle_type, user_name as name, update_time as delete_time from sys_user where del_flag = 0`
+      ` union all select 'role' as recycle_type, role_name as name, update_time as delete_time from sys_role where del_flag = 0`
+      ` union all select 'dept' as recycle_type, dept_name as name, update_time as delete_time from sys_dept where del_flag = 0`
+      `) t where 1 = 1`
+            if recycle_type != '':
+                ` and t.recycle_type = #{recycle_type} `
+            if name != '':
+                ` and t.name like concat('%', #{name}, '%') `
+            if begin_time != '':
+                ` and t.delete_time >= #{begin_time} `
+            if end_time != '':
+                ` and t.delete_time <= #{end_time} `"
+)]
+pub async fn count_recycle_list(rb: &dyn Executor, recycle_type: &str, name: &str, begin_time: &str, end_time: &str) -> rbatis::Result<u64> {
+    impled!()
+}
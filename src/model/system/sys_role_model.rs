@@ -1,8 +1,13 @@
 // author：刘飞华
 // createTime：2024/12/12 14:41:44
 
+use crate::rbatis::rbatis_codegen::IntoSql;
 use crate::vo::system::sys_role_vo::{RoleReq, RoleResp};
+use rbatis::executor::Executor;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
+use rbatis::RBatis;
+use rbs::value;
 use serde::{Deserialize, Serialize};
 /*
  *角色信息
@@ -71,21 +76,21 @@ impl Into<RoleResp> for Role {
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(Role{select_by_id(id:&i64) -> Option => "`where id = #{id} limit 1`"}, "sys_role");
+impl_select!(Role{select_by_id(id:&i64) -> Option => "`where id = #{id} and del_flag = 1 limit 1`"}, "sys_role");
 
 /*
  *根据role_name查询角色信息
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(Role{select_by_role_name(role_name:&str) -> Option => "`where role_name = #{role_name} limit 1`"}, "sys_role");
+impl_select!(Role{select_by_role_name(role_name:&str) -> Option => "`where role_name = #{role_name} and del_flag = 1 limit 1`"}, "sys_role");
 
 /*
  *根据role_key查询角色信息
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(Role{select_by_role_key(role_key:&str) -> Option => "`where role_key = #{role_key} limit 1`"}, "sys_role");
+impl_select!(Role{select_by_role_key(role_key:&str) -> Option => "`where role_key = #{role_key} and del_flag = 1 limit 1`"}, "sys_role");
 
 /*
  *分页查询角色信息
@@ -93,6 +98,7 @@ impl_select!(Role{select_by_role_key(role_key:&str) -> Option => "`where role_ke
  *date：2024/12/12 14:41:44
  */
 impl_select_page!(Role{select_page() =>"
+     ` where del_flag = 1 `
      if !sql.contains('count'):
        order by create_time desc"
 },"sys_role");
@@ -103,7 +109,7 @@ impl_select_page!(Role{select_page() =>"
  *date：2024/12/12 14:41:44
  */
 impl_select_page!(Role{select_sys_role_list(role_name:&str,role_key:&str,status:i8) =>"
-      where 1=1
+      where del_flag = 1
      if role_name != null && role_name != '':
        ` and role_name = #{role_name} `
      if role_key != null && role_key != '':
@@ -112,3 +118,30 @@ impl_select_page!(Role{select_sys_role_list(role_name:&str,role_key:&str,status:
        ` and status = #{status} `
      if !sql.contains('count'):
         ` order by create_time desc `"},"sys_role");
+
+/*
+ *查询用户的正常状态角色(用于计算数据权限)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn select_role_by_user_id(rb: &RBatis, user_id: &i64) -> rbatis::Result<Vec<Role>> {
+    let sql = "select r.* from sys_role r join sys_user_role ur on r.id = ur.role_id where ur.user_id = ? and r.status = 1 and r.del_flag = 1";
+    rb.query_decode(sql, vec![value!(user_id)]).await
+}
+
+/*
+ *逻辑删除角色信息(del_flag设置为0,数据进入回收站)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[py_sql("`update sys_role set del_flag = 0, update_time = now() where del_flag = 1 and id in ${ids.sql()}`")]
+pub async fn soft_delete_role_by_ids(rb: &dyn Executor, ids: &[i64]) -> rbatis::Result<ExecResult> {
+    impled!()
+}
+
+/*
+ *查询所有未删除的角色信息
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(Role{select_all_normal() -> Vec => "`where del_flag = 1`"}, "sys_role");
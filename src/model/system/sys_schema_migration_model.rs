@@ -0,0 +1,32 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+/*
+ *数据库迁移记录
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SchemaMigration {
+    pub version: i64,                   //版本号
+    pub name: String,                   //名称
+    pub checksum: String,               //脚本sha256,防止已执行的脚本被修改
+    pub applied_time: Option<DateTime>, //执行时间
+}
+
+/*
+ *数据库迁移记录基本操作
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+rbatis::crud!(SchemaMigration {}, "schema_migrations");
+
+/*
+ *查询已执行的迁移(按版本号升序)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(SchemaMigration{select_applied() => "`order by version`"}, "schema_migrations");
@@ -0,0 +1,82 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use rbatis::executor::Executor;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
+use rbatis::RBatis;
+use rbs::value;
+use serde::{Deserialize, Serialize};
+
+/*
+ *用户登录失败记录
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserLoginFail {
+    pub id: Option<i64>,                   //主键
+    pub user_id: i64,                      //用户ID
+    pub ip: String,                        //登录IP(按账号锁定时为空)
+    pub fail_count: i32,                   //时间窗口内的失败次数
+    pub first_fail_time: Option<DateTime>, //时间窗口内第一次失败的时间
+    pub lock_until: Option<DateTime>,      //锁定截止时间
+    pub lock_reason: String,               //锁定原因
+    pub update_time: Option<DateTime>,     //修改时间
+}
+
+/*
+ *用户登录失败记录基本操作
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+rbatis::crud!(UserLoginFail {}, "sys_user_login_fail");
+
+/*
+ *根据用户id和IP查询登录失败记录
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(UserLoginFail{select_by_user_ip(user_id:&i64, ip:&str) -> Option => "`where user_id = #{user_id} and ip = #{ip} limit 1`"}, "sys_user_login_fail");
+
+/*
+ *记录一次登录失败(单条语句原子更新,并发请求不会丢失失败次数): 时间窗口已结束时重新计数,否则失败次数加1
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn add_login_fail(rb: &dyn Executor, user_id: i64, ip: &str, now: &DateTime, window_start: &DateTime) -> rbatis::Result<ExecResult> {
+    let sql = "insert into sys_user_login_fail (user_id, ip, fail_count, first_fail_time, lock_reason, update_time) values (?, ?, 1, ?, '', ?) \
+               on duplicate key update fail_count = if(first_fail_time is null or first_fail_time <= ?, 1, fail_count + 1), \
+               first_fail_time = if(first_fail_time is null or first_fail_time <= ?, ?, first_fail_time), update_time = ?";
+    let args = vec![
+        value!(user_id),
+        value!(ip),
+        value!(now),
+        value!(now),
+        value!(window_start),
+        value!(window_start),
+        value!(now),
+        value!(now),
+    ];
+    rb.exec(sql, args).await
+}
+
+/*
+ *失败次数达到上限时锁定(只有一个请求能更新成功,影响行数为0表示没有锁定)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[sql("update sys_user_login_fail set fail_count = 0, lock_until = ?, lock_reason = ? where user_id = ? and ip = ? and fail_count >= ?")]
+pub async fn lock_login_fail(rb: &RBatis, lock_until: &DateTime, lock_reason: &str, user_id: &i64, ip: &str, max_retry: &u32) -> rbatis::Result<ExecResult> {
+    impled!()
+}
+
+/*
+ *删除过期的登录失败记录(时间窗口和锁定时间都已结束)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[sql("delete from sys_user_login_fail where update_time < ?")]
+pub async fn delete_expired_login_fail(rb: &RBatis, before: &DateTime) -> rbatis::Result<ExecResult> {
+    impled!()
+}
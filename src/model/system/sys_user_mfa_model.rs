@@ -0,0 +1,36 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+/*
+ *用户双因素认证
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserMfa {
+    pub id: Option<i64>,               //主键
+    pub user_id: i64,                  //用户ID
+    pub secret: String,                //TOTP密钥(base32)
+    pub status: i8,                    //状态(0:待确认,1:已开启)
+    pub recovery_codes: String,        //恢复码哈希(sha256,逗号分隔,使用后删除)
+    pub last_used_step: i64,           //最后一次使用的时间步(防止验证码重复使用)
+    pub create_time: Option<DateTime>, //创建时间
+    pub update_time: Option<DateTime>, //修改时间
+}
+
+/*
+ *用户双因素认证基本操作
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+rbatis::crud!(UserMfa {}, "sys_user_mfa");
+
+/*
+ *根据用户id查询双因素认证
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(UserMfa{select_by_user_id(user_id:&i64) -> Option => "`where user_id = #{user_id} limit 1`"}, "sys_user_mfa");
@@ -1,9 +1,11 @@
 // author：刘飞华
 // createTime：2024/12/12 14:41:44
 
+use crate::rbatis::rbatis_codegen::IntoSql;
 use crate::vo::system::sys_user_vo::{UserReq, UserResp};
 use rbatis::executor::Executor;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
 use rbatis::rbdc::Error;
 use serde::{Deserialize, Serialize};
 /*
@@ -95,6 +97,8 @@ impl Into<UserResp> for User {
             update_time: self.update_time,                 //修改时间
             dept_info: None,
             post_ids: None,
+            lock_status: 0,
+            lock_reason: None,
         }
     }
 }
@@ -104,35 +108,35 @@ impl Into<UserResp> for User {
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(User{select_by_id(id:i64) -> Option => "`where id = #{id} limit 1`"}, "sys_user");
+impl_select!(User{select_by_id(id:i64) -> Option => "`where id = #{id} and del_flag = 1 limit 1`"}, "sys_user");
 
 /*
  *根据account查询用户信息
  *author：刘飞华
  *date：2025/09/26 13:42:44
  */
-impl_select!(User{select_by_account(account:&str) -> Option => "`where user_name = #{account} or mobile = #{account} or email = #{account} limit 1`"},"sys_user");
+impl_select!(User{select_by_account(account:&str) -> Option => "`where (user_name = #{account} or mobile = #{account} or email = #{account}) and del_flag = 1 limit 1`"},"sys_user");
 
 /*
  *根据mobile查询用户信息
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(User{select_by_mobile(mobile:&str) -> Option => "`where mobile = #{mobile} limit 1`"},"sys_user");
+impl_select!(User{select_by_mobile(mobile:&str) -> Option => "`where mobile = #{mobile} and del_flag = 1 limit 1`"},"sys_user");
 
 /*
  *根据user_name查询用户信息
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(User{select_by_user_name(user_name:&str) -> Option => "`where user_name = #{user_name} limit 1`"}, "sys_user");
+impl_select!(User{select_by_user_name(user_name:&str) -> Option => "`where user_name = #{user_name} and del_flag = 1 limit 1`"}, "sys_user");
 
 /*
  *根据email查询用户信息
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select!(User{select_by_email(email:&str) -> Option => "`where email = #{email} limit 1`"}, "sys_user");
+impl_select!(User{select_by_email(email:&str) -> Option => "`where email = #{email} and del_flag = 1 limit 1`"}, "sys_user");
 
 /*
  *分页查询用户信息
@@ -140,6 +144,7 @@ impl_select!(User{select_by_email(email:&str) -> Option => "`where email = #{ema
  *date：2024/12/12 14:41:44
  */
 impl_select_page!(User{select_page() =>"
+     ` where del_flag = 1 `
      if !sql.contains('count'):
        order by create_time desc"
 },"sys_user");
@@ -149,8 +154,8 @@ impl_select_page!(User{select_page() =>"
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select_page!(User{select_sys_user_list(mobile:&str,user_name:&str,status:i8,dept_id:i64) =>"
-      where 1=1
+impl_select_page!(User{select_sys_user_list(mobile:&str,user_name:&str,status:i8,dept_id:i64,all_data:bool,dept_ids:&[i64]) =>"
+      where del_flag = 1
       if mobile != null && mobile != '':
        ` and mobile = #{mobile} `
      if user_name != null && user_name != '':
@@ -158,7 +163,9 @@ impl_select_page!(User{select_sys_user_list(mobile:&str,user_name:&str,status:i8
      if status != 2:
        ` and status = #{status} `
      if dept_id != 0:
-       ` and (dept_id = #{dept_id} OR dept_id IN (SELECT id FROM sys_dept WHERE find_in_set(#{dept_id}, ancestors))) `
+       ` and (dept_id = #{dept_id} OR dept_id IN (SELECT id FROM sys_dept WHERE del_flag = 1 and find_in_set(#{dept_id}, ancestors))) `
+     if !all_data:
+       ` and dept_id in ${dept_ids.sql()} `
      if !sql.contains('count'):
         ` order by create_time desc `"},"sys_user");
 
@@ -173,9 +180,12 @@ impl_select_page!(User{select_sys_user_list(mobile:&str,user_name:&str,status:i8
                 ` and u.mobile = #{mobile} `
             if user_name != '':
                 ` and u.user_name = #{user_name} `
+            if !all_data:
+                ` and u.dept_id in ${dept_ids.sql()} `
             limit #{page_no},#{page_size}` "
 )]
-async fn select_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, page_no: u64, page_size: u64) -> Result<Vec<User>, Error> {
+#[allow(clippy::too_many_arguments)]
+async fn select_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, all_data: bool, dept_ids: &[i64], page_no: u64, page_size: u64) -> Result<Vec<User>, Error> {
     impled!()
 }
 
@@ -189,9 +199,11 @@ async fn select_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str,
             if mobile != '':
                 ` and u.mobile = #{mobile} `
             if user_name != '':
-                ` and u.user_name = #{user_name} `"
+                ` and u.user_name = #{user_name} `
+            if !all_data:
+                ` and u.dept_id in ${dept_ids.sql()} `"
 )]
-async fn count_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str) -> Result<u64, Error> {
+async fn count_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, all_data: bool, dept_ids: &[i64]) -> Result<u64, Error> {
     impled!()
 }
 
@@ -206,9 +218,12 @@ async fn count_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str,
                 ` and u.mobile = #{mobile} `
             if user_name != '':
                 ` and u.user_name = #{user_name} `
+            if !all_data:
+                ` and u.dept_id in ${dept_ids.sql()} `
             limit #{page_no},#{page_size}` "
 )]
-pub async fn select_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, page_no: u64, page_size: u64) -> rbatis::Result<Vec<User>> {
+#[allow(clippy::too_many_arguments)]
+pub async fn select_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, all_data: bool, dept_ids: &[i64], page_no: u64, page_size: u64) -> rbatis::Result<Vec<User>> {
     impled!()
 }
 
@@ -222,8 +237,20 @@ pub async fn select_unallocated_list(rb: &dyn Executor, role_id: i64, user_name:
             if mobile != '':
                 ` and u.mobile = #{mobile} `
             if user_name != '':
-                ` and u.user_name = #{user_name} `"
+                ` and u.user_name = #{user_name} `
+            if !all_data:
+                ` and u.dept_id in ${dept_ids.sql()} `"
 )]
-pub async fn count_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str) -> rbatis::Result<u64> {
+pub async fn count_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, all_data: bool, dept_ids: &[i64]) -> rbatis::Result<u64> {
+    impled!()
+}
+
+/*
+ *逻辑删除用户信息(del_flag设置为0,数据进入回收站)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[py_sql("`update sys_user set del_flag = 0, update_time = now() where del_flag = 1 and id in ${ids.sql()}`")]
+pub async fn soft_delete_user_by_ids(rb: &dyn Executor, ids: &[i64]) -> rbatis::Result<ExecResult> {
     impled!()
 }
@@ -0,0 +1,32 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+/*
+ *用户历史密码
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserPasswordHistory {
+    pub id: Option<i64>,               //主键
+    pub user_id: i64,                  //用户ID
+    pub password: String,              //密码哈希
+    pub create_time: Option<DateTime>, //创建时间
+}
+
+/*
+ *用户历史密码基本操作
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+rbatis::crud!(UserPasswordHistory {}, "sys_user_password_history");
+
+/*
+ *根据用户id查询历史密码(最近的在前)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(UserPasswordHistory{select_by_user_id(user_id:&i64) => "`where user_id = #{user_id} order by id desc`"}, "sys_user_password_history");
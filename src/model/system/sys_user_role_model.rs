@@ -1,8 +1,12 @@
 // author：刘飞华
 // createTime：2024/12/12 14:41:44
 
+use crate::rbatis::rbatis_codegen::IntoSql;
+use rbatis::executor::Executor;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
 use rbatis::RBatis;
+use rbs::value;
 use serde::{Deserialize, Serialize};
 
 /*
@@ -40,9 +44,12 @@ pub async fn is_admin(rb: &RBatis, user_id: &i64) -> rbatis::Result<i64> {
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-#[sql("select count(1) from sys_user_role where role_id = ?")]
 pub async fn count_user_role_by_role_id(rb: &RBatis, role_id: i64) -> rbatis::Result<i64> {
-    impled!()
+    rb.query_decode(
+        "select count(1) from sys_user_role ur join sys_user u on ur.user_id = u.id where ur.role_id = ? and u.del_flag = 1",
+        vec![value!(role_id)],
+    )
+    .await
 }
 
 /*
@@ -54,3 +61,13 @@ pub async fn count_user_role_by_role_id(rb: &RBatis, role_id: i64) -> rbatis::Re
 pub async fn delete_user_role_by_role_id_user_id(rb: &RBatis, role_id: i64, user_id: i64) -> Option<i64> {
     impled!()
 }
+
+/*
+ *删除用户与已逻辑删除角色的关联(恢复用户时使用,避免角色恢复后用户自动重新获得该角色)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[py_sql("`delete from sys_user_role where user_id in ${user_ids.sql()} and role_id in (select id from sys_role where del_flag = 0)`")]
+pub async fn delete_deleted_role_by_user_ids(rb: &dyn Executor, user_ids: &[i64]) -> rbatis::Result<ExecResult> {
+    impled!()
+}
@@ -0,0 +1,66 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+
+/*
+ *用户刷新令牌
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserToken {
+    pub id: Option<i64>,               //主键
+    pub user_id: i64,                  //用户ID
+    pub token_hash: String,            //刷新令牌哈希(sha256)
+    pub family_id: String,             //令牌族(同一次登录轮换产生的令牌共用)
+    pub replaced_by: Option<i64>,      //轮换后的新令牌ID
+    pub status: i8,                    //状态(1:有效,2:已轮换,0:已撤销)
+    pub expire_time: Option<DateTime>, //过期时间
+    pub create_time: Option<DateTime>, //创建时间
+    pub update_time: Option<DateTime>, //修改时间
+}
+
+/*
+ *用户刷新令牌基本操作
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+rbatis::crud!(UserToken {}, "sys_user_token");
+
+/*
+ *根据令牌哈希查询刷新令牌
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(UserToken{select_by_token_hash(token_hash:&str) -> Option => "`where token_hash = #{token_hash} limit 1`"}, "sys_user_token");
+
+/*
+ *查询令牌族中的第一个令牌(登录时签发)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+impl_select!(UserToken{select_first_by_family_id(family_id:&str) -> Option => "`where family_id = #{family_id} order by id limit 1`"}, "sys_user_token");
+
+/*
+ *撤销整个令牌族(检测到刷新令牌被重复使用时)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[sql("update sys_user_token set status = 0 where family_id = ? and status != 0")]
+pub async fn revoke_token_family(rb: &RBatis, family_id: &str) -> rbatis::Result<ExecResult> {
+    impled!()
+}
+
+/*
+ *撤销用户的所有刷新令牌(禁用、删除用户或修改密码)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[sql("update sys_user_token set status = 0 where user_id = ? and status != 0")]
+pub async fn revoke_user_token(rb: &RBatis, user_id: &i64) -> rbatis::Result<ExecResult> {
+    impled!()
+}
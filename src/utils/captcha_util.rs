@@ -0,0 +1,199 @@
+use crate::common::config::APP_CONFIG;
+use crate::common::error::{AppError, AppResult};
+use crate::utils::png_util::encode_gray;
+use crate::utils::token_util::random_token;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const WIDTH: u32 = 120;
+const HEIGHT: u32 = 40;
+const CODE_LEN: usize = 4;
+const SCALE: u32 = 4;
+
+// 数字0-9的5x7点阵字模,每行低5位有效
+const DIGITS: [[u8; 7]; 10] = [
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+];
+
+// 每个IP获取验证码的计数窗口(秒)
+const IP_WINDOW_SECS: u64 = 60;
+
+lazy_static! {
+    // 验证码: captcha_key -> (答案, 过期时间)
+    static ref CAPTCHA_STORE: RwLock<HashMap<String, (String, u64)>> = RwLock::new(HashMap::new());
+    // 每个IP获取验证码的次数: ip -> (次数, 窗口开始时间)
+    static ref IP_COUNTER: RwLock<HashMap<String, (u32, u64)>> = RwLock::new(HashMap::new());
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs()
+}
+
+/*
+ *生成验证码,返回(captcha_key, png图片)
+ *同一IP获取过于频繁或者保存的验证码达到上限时拒绝生成
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn create_captcha(ipaddr: &str) -> AppResult<(String, Vec<u8>)> {
+    let config = &APP_CONFIG.captcha;
+    let now = now_secs();
+    if !check_ip_limit(ipaddr, config.ip_limit, now) {
+        return Err(AppError::BusinessError("获取验证码过于频繁,请稍后再试"));
+    }
+
+    let mut rng = rand::thread_rng();
+    let code = (0..CODE_LEN).map(|_| rng.gen_range(0..10u8)).collect::<Vec<u8>>();
+    let answer = code.iter().map(|x| (b'0' + x) as char).collect::<String>();
+    let key = random_token();
+    {
+        let mut store = CAPTCHA_STORE.write().unwrap();
+        // 只在达到上限时清理过期的验证码,避免每次请求都遍历
+        if store.len() >= config.max_entries {
+            store.retain(|_, (_, expire)| *expire > now);
+        }
+        if store.len() >= config.max_entries {
+            return Err(AppError::BusinessError("验证码请求过多,请稍后再试"));
+        }
+        store.insert(key.clone(), (answer, now + config.expire_secs));
+    }
+
+    Ok((key, draw_captcha(&code)))
+}
+
+// 同一IP在计数窗口内获取的次数不能超过limit(0表示不限制)
+fn check_ip_limit(ipaddr: &str, limit: u32, now: u64) -> bool {
+    if limit == 0 {
+        return true;
+    }
+
+    let mut counter = IP_COUNTER.write().unwrap();
+    if counter.len() >= APP_CONFIG.captcha.max_entries {
+        counter.retain(|_, (_, start)| *start + IP_WINDOW_SECS > now);
+    }
+
+    let entry = counter.entry(ipaddr.to_string()).or_insert((0, now));
+    if entry.1 + IP_WINDOW_SECS <= now {
+        *entry = (0, now);
+    }
+    if entry.0 >= limit {
+        return false;
+    }
+    entry.0 += 1;
+    true
+}
+
+/*
+ *校验验证码(无论是否正确,验证码只能使用一次)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn verify_captcha(key: &str, code: &str) -> bool {
+    match CAPTCHA_STORE.write().unwrap().remove(key) {
+        Some((answer, expire)) => expire > now_secs() && answer.eq_ignore_ascii_case(code.trim()),
+        None => false,
+    }
+}
+
+fn draw_captcha(code: &[u8]) -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    let mut pixels = vec![0u8; (WIDTH * HEIGHT) as usize];
+    for x in pixels.iter_mut() {
+        *x = rng.gen_range(215..=255);
+    }
+
+    // 字符随机偏移和深浅
+    let glyph_width = 5 * SCALE;
+    let step = (WIDTH - 8) / CODE_LEN as u32;
+    for (i, &digit) in code.iter().enumerate() {
+        let ox = 4 + i as u32 * step + rng.gen_range(0..=step - glyph_width);
+        let oy = rng.gen_range(0..=HEIGHT - 7 * SCALE);
+        let color = rng.gen_range(0..90u8);
+        for (row, bits) in DIGITS[digit as usize].iter().enumerate() {
+            for col in 0..5u32 {
+                if bits & (0x10 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..SCALE {
+                    for dx in 0..SCALE {
+                        let (x, y) = (ox + col * SCALE + dx, oy + row as u32 * SCALE + dy);
+                        pixels[(y * WIDTH + x) as usize] = color;
+                    }
+                }
+            }
+        }
+    }
+
+    // 干扰线
+    for _ in 0..4 {
+        let (x0, y0) = (rng.gen_range(0..WIDTH) as i32, rng.gen_range(0..HEIGHT) as i32);
+        let (x1, y1) = (rng.gen_range(0..WIDTH) as i32, rng.gen_range(0..HEIGHT) as i32);
+        draw_line(&mut pixels, (x0, y0), (x1, y1), rng.gen_range(60..160));
+    }
+
+    // 干扰点
+    for _ in 0..(WIDTH * HEIGHT / 20) {
+        let (x, y) = (rng.gen_range(0..WIDTH), rng.gen_range(0..HEIGHT));
+        pixels[(y * WIDTH + x) as usize] = rng.gen_range(0..200);
+    }
+
+    encode_gray(WIDTH, HEIGHT, &pixels)
+}
+
+fn draw_line(pixels: &mut [u8], (mut x0, mut y0): (i32, i32), (x1, y1): (i32, i32), color: u8) {
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+    let mut err = dx + dy;
+    loop {
+        pixels[(y0 as u32 * WIDTH + x0 as u32) as usize] = color;
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::captcha_util::{check_ip_limit, create_captcha, verify_captcha, CAPTCHA_STORE};
+
+    #[test]
+    fn test_captcha() {
+        let (key, image) = create_captcha("127.0.0.1").unwrap();
+        assert_eq!(&image[1..4], b"PNG");
+
+        let answer = CAPTCHA_STORE.read().unwrap().get(&key).unwrap().0.clone();
+        assert!(!verify_captcha("not_exist", &answer));
+        assert!(verify_captcha(&key, &answer));
+        assert!(!verify_captcha(&key, &answer));
+    }
+
+    #[test]
+    fn test_ip_limit() {
+        let ip = "test_ip_limit";
+        assert!(check_ip_limit(ip, 2, 1000));
+        assert!(check_ip_limit(ip, 2, 1010));
+        assert!(!check_ip_limit(ip, 2, 1020));
+        assert!(check_ip_limit(ip, 2, 1060));
+        assert!(check_ip_limit(ip, 0, 1060));
+    }
+}
@@ -0,0 +1,65 @@
+use crate::common::error::AppResult;
+use crate::model::system::sys_dept_model::select_children_dept_by_id;
+use crate::model::system::sys_role_dept_model::RoleDept;
+use crate::model::system::sys_role_model::select_role_by_user_id;
+use crate::model::system::sys_user_model::User;
+use crate::model::system::sys_user_role_model::is_admin;
+use crate::RB;
+use rbs::value;
+use std::collections::HashSet;
+
+/*
+ *数据权限范围(多个角色取并集)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct DataScope {
+    pub all: bool,          //是否为全部数据权限
+    pub dept_ids: Vec<i64>, //可访问的部门ids(不会为空,没有部门时为-1)
+}
+
+/*
+ *查询用户的数据权限范围
+ *data_scope: 1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn query_data_scope(user_id: i64) -> AppResult<DataScope> {
+    let rb = &mut RB.clone();
+
+    if is_admin(rb, &user_id).await? == 1 {
+        return Ok(DataScope { all: true, dept_ids: vec![-1] });
+    }
+
+    let user_dept_id = User::select_by_id(rb, user_id).await?.map(|x| x.dept_id);
+    let roles = select_role_by_user_id(rb, &user_id).await?;
+
+    let mut dept_ids: HashSet<i64> = HashSet::new();
+    let mut custom_role_ids: Vec<i64> = Vec::new();
+    for role in roles {
+        match role.data_scope {
+            1 => return Ok(DataScope { all: true, dept_ids: vec![-1] }),
+            2 => custom_role_ids.push(role.id.unwrap_or_default()),
+            3 => dept_ids.extend(user_dept_id),
+            4 => {
+                if let Some(dept_id) = user_dept_id {
+                    dept_ids.insert(dept_id);
+                    dept_ids.extend(select_children_dept_by_id(rb, &dept_id).await?.into_iter().filter_map(|x| x.id));
+                }
+            }
+            _ => log::warn!("unknown data_scope: {:?}, role_id: {:?}", role.data_scope, role.id),
+        }
+    }
+
+    if !custom_role_ids.is_empty() {
+        dept_ids.extend(RoleDept::select_by_map(rb, value! {"role_id": &custom_role_ids}).await?.into_iter().map(|x| x.dept_id));
+    }
+
+    let mut dept_ids = dept_ids.into_iter().collect::<Vec<i64>>();
+    if dept_ids.is_empty() {
+        dept_ids.push(-1);
+    }
+
+    Ok(DataScope { all: false, dept_ids })
+}
@@ -0,0 +1,121 @@
+use crate::common::config::APP_CONFIG;
+use std::net::IpAddr;
+
+// ip2region xdb文件格式: 256字节头部 + 256*256*8字节向量索引 + 数据区 + 二分索引区(每条14字节)
+const HEADER_INFO_LENGTH: usize = 256;
+const VECTOR_INDEX_COLS: usize = 256;
+const VECTOR_INDEX_SIZE: usize = 8;
+const SEGMENT_INDEX_SIZE: usize = 14;
+
+lazy_static! {
+    // 离线IP地址库(ip2region xdb),未配置或文件无效时不解析登录地点
+    static ref IP_REGION_DB: Option<Vec<u8>> = Some(&APP_CONFIG.log.ip_region_db).filter(|x| !x.is_empty()).and_then(|x| load_db(x));
+}
+
+fn load_db(path: &str) -> Option<Vec<u8>> {
+    match std::fs::read(path) {
+        Ok(x) if x.len() > HEADER_INFO_LENGTH + VECTOR_INDEX_COLS * VECTOR_INDEX_COLS * VECTOR_INDEX_SIZE => Some(x),
+        Ok(_) => {
+            log::warn!("invalid ip region db: {}", path);
+            None
+        }
+        Err(err) => {
+            log::warn!("load ip region db: {} error: {}", path, err);
+            None
+        }
+    }
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(buf.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(buf.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+/*
+ *在xdb中查询IPv4的区域信息,格式: 国家|区域|省份|城市|ISP
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn search(db: &[u8], ip: u32) -> Option<String> {
+    let il0 = ((ip >> 24) & 0xFF) as usize;
+    let il1 = ((ip >> 16) & 0xFF) as usize;
+    let idx = HEADER_INFO_LENGTH + il0 * VECTOR_INDEX_COLS * VECTOR_INDEX_SIZE + il1 * VECTOR_INDEX_SIZE;
+    let s_ptr = read_u32(db, idx)? as usize;
+    let e_ptr = read_u32(db, idx + 4)? as usize;
+    if e_ptr < s_ptr {
+        return None;
+    }
+
+    let (mut l, mut h) = (0i64, ((e_ptr - s_ptr) / SEGMENT_INDEX_SIZE) as i64);
+    while l <= h {
+        let m = (l + h) / 2;
+        let p = s_ptr + m as usize * SEGMENT_INDEX_SIZE;
+        if ip < read_u32(db, p)? {
+            h = m - 1;
+        } else if ip > read_u32(db, p + 4)? {
+            l = m + 1;
+        } else {
+            let data_len = read_u16(db, p + 8)? as usize;
+            let data_ptr = read_u32(db, p + 10)? as usize;
+            return db.get(data_ptr..data_ptr + data_len).map(|x| String::from_utf8_lossy(x).to_string());
+        }
+    }
+    None
+}
+
+/*
+ *区域信息转换为登录地点,去掉为0的字段和ISP,例如: 中国|0|广东省|深圳市|电信 => 中国 广东省 深圳市
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn format_region(region: &str) -> String {
+    region.split('|').take(4).filter(|x| !x.is_empty() && *x != "0").collect::<Vec<&str>>().join(" ")
+}
+
+/*
+ *根据IP查询登录地点
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn ip_location(ip: &str) -> String {
+    let ip = match ip.parse::<IpAddr>() {
+        Ok(x) => x,
+        Err(_) => return "未知".to_string(),
+    };
+
+    let ip = match ip {
+        IpAddr::V4(x) if x.is_private() || x.is_loopback() || x.is_link_local() => return "内网IP".to_string(),
+        IpAddr::V4(x) => x,
+        IpAddr::V6(x) if x.is_loopback() => return "内网IP".to_string(),
+        IpAddr::V6(x) => match x.to_ipv4_mapped() {
+            Some(x) => x,
+            None => return "未知".to_string(),
+        },
+    };
+
+    match IP_REGION_DB.as_deref().and_then(|db| search(db, u32::from(ip))) {
+        Some(region) => format_region(&region),
+        None => "未知".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::ip_region_util::{format_region, ip_location};
+
+    #[test]
+    fn test_format_region() {
+        assert_eq!(format_region("中国|0|广东省|深圳市|电信"), "中国 广东省 深圳市");
+        assert_eq!(format_region("0|0|0|内网IP|内网IP"), "内网IP");
+    }
+
+    #[test]
+    fn test_private_ip() {
+        assert_eq!(ip_location("192.168.1.1"), "内网IP");
+        assert_eq!(ip_location("127.0.0.1"), "内网IP");
+        assert_eq!(ip_location("abc"), "未知");
+    }
+}
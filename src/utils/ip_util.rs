@@ -0,0 +1,137 @@
+use crate::common::config::APP_CONFIG;
+use ntex::http::HeaderMap;
+use std::net::{IpAddr, SocketAddr};
+
+lazy_static! {
+    // 可信代理(server.trusted_proxies,启动时已校验格式),只有来自可信代理的请求才读取X-Forwarded-For/X-Real-IP
+    static ref TRUSTED_PROXIES: Vec<IpNet> = APP_CONFIG.server.trusted_proxies.iter().filter_map(|x| IpNet::parse(x)).collect();
+}
+
+/*
+ *IP网段
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpNet {
+    addr: IpAddr, //网络地址
+    prefix: u8,   //前缀长度
+}
+
+impl IpNet {
+    pub fn parse(s: &str) -> Option<IpNet> {
+        let (addr, prefix) = match s.trim().split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (s.trim().parse::<IpAddr>().ok()?, None),
+        };
+
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        if prefix > max {
+            return None;
+        }
+        Some(IpNet { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(*ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_trusted(trusted: &[IpNet], ip: &IpAddr) -> bool {
+    trusted.iter().any(|x| x.contains(ip))
+}
+
+/*
+ *解析客户端真实IP
+ *X-Forwarded-For从右往左跳过可信代理,第一个不可信的地址即为客户端地址
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn resolve_ip(peer: IpAddr, forwarded_for: Option<&str>, real_ip: Option<&str>, trusted: &[IpNet]) -> IpAddr {
+    if !is_trusted(trusted, &peer) {
+        return peer;
+    }
+
+    if let Some(forwarded_for) = forwarded_for {
+        let mut client = None;
+        for x in forwarded_for.rsplit(',') {
+            match x.trim().parse::<IpAddr>() {
+                Ok(ip) => {
+                    client = Some(ip);
+                    if !is_trusted(trusted, &ip) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        if let Some(ip) = client {
+            return ip;
+        }
+    }
+
+    real_ip.and_then(|x| x.trim().parse::<IpAddr>().ok()).unwrap_or(peer)
+}
+
+/*
+ *获取客户端真实IP(请求头只信任来自TRUSTED_PROXIES的请求)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn client_ip(peer: Option<SocketAddr>, headers: &HeaderMap) -> String {
+    let peer = match peer {
+        None => return "".to_string(),
+        Some(x) => x.ip(),
+    };
+
+    let forwarded_for = headers.get("X-Forwarded-For").and_then(|x| x.to_str().ok());
+    let real_ip = headers.get("X-Real-IP").and_then(|x| x.to_str().ok());
+    resolve_ip(peer, forwarded_for, real_ip, &TRUSTED_PROXIES).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::ip_util::{resolve_ip, IpNet};
+    use std::net::IpAddr;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ip_net() {
+        assert!(IpNet::parse("10.0.0.0/8").unwrap().contains(&ip("10.1.2.3")));
+        assert!(!IpNet::parse("10.0.0.0/8").unwrap().contains(&ip("11.1.2.3")));
+        assert!(IpNet::parse("127.0.0.1").unwrap().contains(&ip("127.0.0.1")));
+        assert!(IpNet::parse("0.0.0.0/0").unwrap().contains(&ip("8.8.8.8")));
+        assert!(IpNet::parse("::1").unwrap().contains(&ip("::1")));
+        assert!(IpNet::parse("10.0.0.0/33").is_none());
+        assert!(IpNet::parse("abc").is_none());
+    }
+
+    #[test]
+    fn test_untrusted_peer() {
+        let trusted = vec![IpNet::parse("10.0.0.0/8").unwrap()];
+        assert_eq!(resolve_ip(ip("1.2.3.4"), Some("5.6.7.8"), Some("5.6.7.8"), &trusted), ip("1.2.3.4"));
+    }
+
+    #[test]
+    fn test_trusted_peer() {
+        let trusted = vec![IpNet::parse("10.0.0.0/8").unwrap()];
+        assert_eq!(resolve_ip(ip("10.0.0.1"), Some("9.9.9.9, 5.6.7.8, 10.0.0.2"), None, &trusted), ip("5.6.7.8"));
+        assert_eq!(resolve_ip(ip("10.0.0.1"), None, Some("5.6.7.8"), &trusted), ip("5.6.7.8"));
+        assert_eq!(resolve_ip(ip("10.0.0.1"), Some("10.0.0.3"), None, &trusted), ip("10.0.0.3"));
+        assert_eq!(resolve_ip(ip("10.0.0.1"), None, None, &trusted), ip("10.0.0.1"));
+    }
+}
@@ -1,19 +1,85 @@
+use std::fs;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::common::config::{JwtSettings, APP_CONFIG};
 use crate::common::error::AppError;
-use crate::common::error::AppError::JwtTokenError;
+use crate::common::error::AppError::{ConfigError, JwtTokenError};
+use crate::utils::token_util::random_token;
 use jsonwebtoken::{decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 
+// (subject)：主题
+const SUBJECT: &str = "rust_admin";
+
+lazy_static! {
+    pub static ref JWT_CONFIG: JwtConfig = JwtConfig::from_config(&APP_CONFIG.jwt).expect("load jwt config error");
+}
+
+/*
+ *jwt配置(由config.toml中的[jwt]生成签名和验证密钥)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Clone)]
+pub struct JwtConfig {
+    pub algorithm: Algorithm,
+    pub encoding_key: EncodingKey,
+    pub decoding_key: DecodingKey,
+    pub expire_secs: u64,
+    pub refresh_expire_secs: u64,
+    pub issuer: String,
+    pub audience: String,
+}
+
+impl JwtConfig {
+    pub fn from_config(settings: &JwtSettings) -> Result<JwtConfig, AppError> {
+        let algorithm = match settings.algorithm.as_str() {
+            "HS256" => Algorithm::HS256,
+            "RS256" => Algorithm::RS256,
+            "EdDSA" => Algorithm::EdDSA,
+            other => return Err(ConfigError(format!("jwt.algorithm 不支持: {}", other))),
+        };
+
+        let (encoding_key, decoding_key) = match algorithm {
+            Algorithm::HS256 => (EncodingKey::from_secret(settings.secret.as_ref()), DecodingKey::from_secret(settings.secret.as_ref())),
+            _ => {
+                let private_key = read_key_file(&settings.private_key_file)?;
+                let public_key = read_key_file(&settings.public_key_file)?;
+                let keys = if algorithm == Algorithm::RS256 {
+                    EncodingKey::from_rsa_pem(&private_key).and_then(|e| DecodingKey::from_rsa_pem(&public_key).map(|d| (e, d)))
+                } else {
+                    EncodingKey::from_ed_pem(&private_key).and_then(|e| DecodingKey::from_ed_pem(&public_key).map(|d| (e, d)))
+                };
+                keys.map_err(|err| ConfigError(format!("jwt 密钥文件格式错误: {}", err)))?
+            }
+        };
+
+        Ok(JwtConfig {
+            algorithm,
+            encoding_key,
+            decoding_key,
+            expire_secs: settings.expire_secs,
+            refresh_expire_secs: settings.refresh_expire_secs,
+            issuer: settings.issuer.clone(),
+            audience: settings.audience.clone(),
+        })
+    }
+}
+
+fn read_key_file(path: &str) -> Result<Vec<u8>, AppError> {
+    fs::read(path).map_err(|err| ConfigError(format!("读取密钥文件 {} 失败: {}", path, err)))
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JwtToken {
     pub id: i64,
     pub username: String,
-    pub permissions: Vec<String>,
+    pub pv: u64,     // 权限版本号(签发时的权限版本,权限本身由JwtAuth从缓存查询)
+    pub sid: String, // 会话ID(同一次登录刷新得到的令牌共用,即刷新令牌族)
     aud: String,
     // (audience)：受众
-    exp: usize,
-    iat: usize,
+    pub exp: usize,
+    pub iat: usize,
     // (Issued At)：签发时间
     iss: String,
     // (issuer)：签发人
@@ -21,76 +87,87 @@ pub struct JwtToken {
     // (Not Before)：生效时间
     sub: String,
     // (subject)：主题
-    jti: String, // (JWT ID)：编号
+    pub jti: String, // (JWT ID)：编号
 }
 
 impl JwtToken {
-    pub fn new(id: i64, username: &str, permissions: Vec<String>) -> JwtToken {
+    pub fn new(config: &JwtConfig, id: i64, username: &str, pv: u64, sid: &str) -> JwtToken {
         let now = SystemTime::now();
         //过期时间
-        let m30 = Duration::from_secs(1800000);
+        let expire = Duration::from_secs(config.expire_secs);
         let now = now.duration_since(UNIX_EPOCH).expect("获取系统时间失败");
 
         JwtToken {
             id,
             username: String::from(username),
-            permissions,
-            aud: String::from("rust_admin"), // (audience)：受众
-            exp: (now + m30).as_secs() as usize,
-            iat: now.as_secs() as usize,     // (Issued At)：签发时间
-            iss: String::from("koobe"),      // (issuer)：签发人
-            nbf: now.as_secs() as usize,     // (Not Before)：生效时间
-            sub: String::from("rust_admin"), // (subject)：主题
-            jti: String::from("ignore"),     // (JWT ID)：编号
+            pv,
+            sid: String::from(sid),
+            aud: config.audience.clone(), // (audience)：受众
+            exp: (now + expire).as_secs() as usize,
+            iat: now.as_secs() as usize, // (Issued At)：签发时间
+            iss: config.issuer.clone(),  // (issuer)：签发人
+            nbf: now.as_secs() as usize, // (Not Before)：生效时间
+            sub: String::from(SUBJECT),  // (subject)：主题
+            jti: random_token(),         // (JWT ID)：编号
         }
     }
 
     /// create token
-    /// secret: your secret string
-    pub fn create_token(&self, secret: &str) -> Result<String, AppError> {
-        return match encode(&Header::default(), self, &EncodingKey::from_secret(secret.as_ref())) {
+    /// config: jwt signing config
+    pub fn create_token(&self, config: &JwtConfig) -> Result<String, AppError> {
+        match encode(&Header::new(config.algorithm), self, &config.encoding_key) {
             Ok(t) => Ok(t),
             Err(_) => Err(JwtTokenError("create token error".to_string())),
-        };
+        }
     }
     /// verify token invalid
-    /// secret: your secret string
-    pub fn verify(secret: &str, token: &str) -> Result<JwtToken, AppError> {
-        let mut validation = Validation::new(Algorithm::HS256);
-        validation.sub = Some("rust_admin".to_string());
-        validation.set_audience(&["rust_admin"]);
-        validation.set_required_spec_claims(&["exp", "sub", "aud"]);
-        return match decode::<JwtToken>(&token, &DecodingKey::from_secret(secret.as_ref()), &validation) {
+    /// config: jwt signing config
+    pub fn verify(config: &JwtConfig, token: &str) -> Result<JwtToken, AppError> {
+        let mut validation = Validation::new(config.algorithm);
+        validation.sub = Some(SUBJECT.to_string());
+        validation.set_audience(&[&config.audience]);
+        validation.set_issuer(&[&config.issuer]);
+        validation.set_required_spec_claims(&["exp", "sub", "aud", "iss"]);
+        match decode::<JwtToken>(token, &config.decoding_key, &validation) {
             Ok(c) => Ok(c.claims),
 
             Err(err) => match *err.kind() {
-                ErrorKind::InvalidToken => return Err(JwtTokenError("InvalidToken".to_string())), // Example on how to handle a specific error
-                ErrorKind::InvalidIssuer => return Err(JwtTokenError("InvalidIssuer".to_string())), // Example on how to handle a specific error
-                ErrorKind::ExpiredSignature => return Err(JwtTokenError("token 已经超时了".to_string())), // Example on how to handle a specific error
+                ErrorKind::InvalidToken => Err(JwtTokenError("InvalidToken".to_string())),         // Example on how to handle a specific error
+                ErrorKind::InvalidIssuer => Err(JwtTokenError("InvalidIssuer".to_string())),       // Example on how to handle a specific error
+                ErrorKind::ExpiredSignature => Err(JwtTokenError("token 已经超时了".to_string())), // Example on how to handle a specific error
                 // _ => return Err(Error::from("InvalidToken other errors")),
                 _ => Err(JwtTokenError("verify token error".to_string())),
             },
-        };
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::jwt_util::JwtToken;
+    use crate::utils::jwt_util::{JwtConfig, JwtToken};
+    use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+
+    fn hs256_config(secret: &str) -> JwtConfig {
+        JwtConfig {
+            algorithm: Algorithm::HS256,
+            encoding_key: EncodingKey::from_secret(secret.as_ref()),
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+            expire_secs: 900,
+            refresh_expire_secs: 604800,
+            issuer: "koobe".to_string(),
+            audience: "rust_admin".to_string(),
+        }
+    }
 
     #[test]
     fn test_jwt() {
-        let jwt = JwtToken::new(1, "koobe", vec![]);
-        let res = jwt.create_token("123");
-        match res {
-            Ok(token) => {
-                println!("{:?}", token);
-                let token = JwtToken::verify("123", &token);
-                println!("{:?}", token)
-            }
-            Err(err) => {
-                println!("{:?}", err)
-            }
-        }
+        let config = hs256_config("123");
+        let jwt = JwtToken::new(&config, 1, "koobe", 1, "sid");
+        let token = jwt.create_token(&config).unwrap();
+        let verified = JwtToken::verify(&config, &token).unwrap();
+        assert_eq!(verified.id, 1);
+        assert_eq!(verified.username, "koobe");
+
+        assert!(JwtToken::verify(&hs256_config("456"), &token).is_err());
     }
 }
@@ -0,0 +1,206 @@
+use crate::common::config::{LoginLockConfig, APP_CONFIG};
+use crate::common::error::AppResult;
+use crate::model::system::sys_user_login_fail_model::{add_login_fail, delete_expired_login_fail, lock_login_fail, UserLoginFail};
+use crate::RB;
+use rbatis::rbdc::datetime::DateTime;
+use rbs::value;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    pub static ref LOCK_POLICY: LockPolicy = LockPolicy::from_config(&APP_CONFIG.login_lock);
+    // IP最近一次登录失败的时间(包括账号不存在),只用于判断是否需要验证码
+    // 账号的失败次数和锁定状态保存在sys_user_login_fail表中
+    static ref IP_FAILURE: RwLock<HashMap<String, u64>> = RwLock::new(HashMap::new());
+}
+
+/*
+ *登录锁定策略
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct LockPolicy {
+    pub max_retry: u32,   //时间窗口内允许的最大失败次数(0表示不锁定)
+    pub window_secs: u64, //失败次数统计的时间窗口(秒)
+    pub lock_secs: u64,   //锁定时长(秒)
+    pub by_ip: bool,      //是否按账号+IP锁定(默认只按账号锁定)
+}
+
+impl LockPolicy {
+    pub fn from_config(config: &LoginLockConfig) -> LockPolicy {
+        LockPolicy {
+            max_retry: config.max_retry,
+            window_secs: config.window_secs,
+            lock_secs: config.lock_secs,
+            by_ip: config.lock_by == "account_ip",
+        }
+    }
+}
+
+/*
+ *用户锁定信息
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct LockInfo {
+    pub remaining_secs: u64, //剩余锁定时间(秒)
+    pub reason: String,      //锁定原因
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs()
+}
+
+// 按账号锁定时失败记录的IP为空
+fn lock_ip(policy: &LockPolicy, ip: &str) -> String {
+    if policy.by_ip {
+        ip.to_string()
+    } else {
+        "".to_string()
+    }
+}
+
+fn locked_info(failure: &UserLoginFail, now: i64) -> Option<LockInfo> {
+    let lock_until = failure.lock_until.as_ref()?.unix_timestamp();
+    (lock_until > now).then(|| LockInfo {
+        remaining_secs: (lock_until - now) as u64,
+        reason: failure.lock_reason.clone(),
+    })
+}
+
+/*
+ *查询账号(或账号+IP)是否锁定
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn check_locked(user_id: i64, ip: &str) -> AppResult<Option<LockInfo>> {
+    let rb = &mut RB.clone();
+    let failure = UserLoginFail::select_by_user_ip(rb, &user_id, &lock_ip(&LOCK_POLICY, ip)).await?;
+    Ok(failure.and_then(|x| locked_info(&x, DateTime::now().unix_timestamp())))
+}
+
+/*
+ *记录登录失败,达到最大失败次数后锁定,返回锁定信息
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn record_failure(user_id: i64, ip: &str) -> AppResult<Option<LockInfo>> {
+    let policy = &LOCK_POLICY;
+    if policy.max_retry == 0 {
+        return Ok(None);
+    }
+
+    let rb = &mut RB.clone();
+    let now = DateTime::now();
+    let expire_secs = policy.window_secs.max(policy.lock_secs) as i64;
+    delete_expired_login_fail(rb, &now.clone().add_sub_sec(-expire_secs)).await?;
+
+    let key_ip = lock_ip(policy, ip);
+    let window_start = now.clone().add_sub_sec(-(policy.window_secs as i64));
+    add_login_fail(rb, user_id, &key_ip, &now, &window_start).await?;
+
+    let reason = if policy.by_ip {
+        format!("IP {} 在{}分钟内密码错误{}次", ip, policy.window_secs / 60, policy.max_retry)
+    } else {
+        format!("{}分钟内密码错误{}次", policy.window_secs / 60, policy.max_retry)
+    };
+    let lock_until = now.add_sub_sec(policy.lock_secs as i64);
+    if lock_login_fail(rb, &lock_until, &reason, &user_id, &key_ip, &policy.max_retry).await?.rows_affected == 0 {
+        return Ok(None);
+    }
+    log::warn!("lock user_id: {}, ip: {}, reason: {}", user_id, ip, reason);
+
+    Ok(Some(LockInfo {
+        remaining_secs: policy.lock_secs,
+        reason,
+    }))
+}
+
+/*
+ *记录IP登录失败
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn record_ip_failure(ip: &str) {
+    let now = now_secs();
+    let mut failures = IP_FAILURE.write().unwrap();
+    failures.retain(|_, x| *x + LOCK_POLICY.window_secs > now);
+    failures.insert(ip.to_string(), now);
+}
+
+/*
+ *账号或IP在时间窗口内是否有登录失败(有则登录需要验证码)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn has_recent_failure(user_id: Option<i64>, ip: &str) -> AppResult<bool> {
+    let window_secs = LOCK_POLICY.window_secs;
+    if IP_FAILURE.read().unwrap().get(ip).is_some_and(|x| *x + window_secs > now_secs()) {
+        return Ok(true);
+    }
+
+    let user_id = match user_id {
+        None => return Ok(false),
+        Some(x) => x,
+    };
+
+    let rb = &mut RB.clone();
+    let now = DateTime::now().unix_timestamp();
+    let failures = UserLoginFail::select_by_map(rb, value! {"user_id": user_id}).await?;
+    Ok(failures.iter().any(|x| {
+        let first_fail = x.first_fail_time.as_ref().map_or(0, |x| x.unix_timestamp());
+        locked_info(x, now).is_some() || (x.fail_count > 0 && first_fail + window_secs as i64 > now)
+    }))
+}
+
+/*
+ *登录成功后清除失败记录
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn clear_failure(user_id: i64, ip: &str) -> AppResult<()> {
+    let rb = &mut RB.clone();
+    UserLoginFail::delete_by_map(rb, value! {"user_id": user_id, "ip": lock_ip(&LOCK_POLICY, ip)}).await?;
+    Ok(())
+}
+
+/*
+ *解锁用户(清除用户所有的失败和锁定记录)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn unlock_user(user_ids: &[i64]) -> AppResult<()> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    let rb = &mut RB.clone();
+    UserLoginFail::delete_by_map(rb, value! {"user_id": user_ids}).await?;
+    Ok(())
+}
+
+/*
+ *批量查询用户的锁定信息(用于用户列表展示,按账号+IP锁定时任意IP被锁定即视为锁定)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn lock_info(user_ids: &[i64]) -> AppResult<HashMap<i64, LockInfo>> {
+    let mut locks: HashMap<i64, LockInfo> = HashMap::new();
+    if user_ids.is_empty() {
+        return Ok(locks);
+    }
+
+    let rb = &mut RB.clone();
+    let now = DateTime::now().unix_timestamp();
+    for failure in UserLoginFail::select_by_map(rb, value! {"user_id": user_ids}).await? {
+        if let Some(lock) = locked_info(&failure, now) {
+            if locks.get(&failure.user_id).is_none_or(|x| x.remaining_secs < lock.remaining_secs) {
+                locks.insert(failure.user_id, lock);
+            }
+        }
+    }
+    Ok(locks)
+}
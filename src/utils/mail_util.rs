@@ -0,0 +1,170 @@
+use crate::common::config::{MailConfig, APP_CONFIG};
+use crate::common::error::{AppError, AppResult};
+use futures::future::BoxFuture;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    // 邮件发送方式: mail.sender为smtp时使用SMTP服务器发送,默认写入文件(本地开发测试不需要邮件服务器)
+    pub static ref MAIL_SENDER: Box<dyn MailSender> = create_mail_sender(&APP_CONFIG.mail);
+}
+
+/*
+ *邮件
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,      //收件人
+    pub subject: String, //主题
+    pub body: String,    //正文(纯文本)
+}
+
+/*
+ *邮件发送(可替换的实现)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub trait MailSender: Send + Sync {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, AppResult<()>>;
+}
+
+/*
+ *通过SMTP服务器发送邮件
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub struct SmtpMailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailSender {
+    // tls: tls(默认,端口465), starttls(端口587), none(不加密,本地测试邮件服务器)
+    pub fn new(host: &str, port: u16, tls: &str, username: &str, password: &str, from: &str) -> AppResult<SmtpMailSender> {
+        let from = from.parse::<Mailbox>().map_err(|err| AppError::ConfigError(format!("mail.from: {}", err)))?;
+        let builder = match tls {
+            "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            _ => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        }
+        .map_err(|err| AppError::ConfigError(format!("mail.smtp_host: {}", err)))?;
+
+        let mut builder = builder.port(port);
+        if !username.is_empty() {
+            builder = builder.credentials(Credentials::new(username.to_string(), password.to_string()));
+        }
+        Ok(SmtpMailSender { transport: builder.build(), from })
+    }
+}
+
+impl MailSender for SmtpMailSender {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, AppResult<()>> {
+        Box::pin(async move {
+            let to = mail.to.parse::<Mailbox>().map_err(|err| {
+                log::error!("parse mail to: {} error: {:?}", mail.to, err);
+                AppError::BusinessError("收件人邮箱格式不正确")
+            })?;
+            let message = Message::builder().from(self.from.clone()).to(to).subject(mail.subject).body(mail.body).map_err(|err| {
+                log::error!("build mail error: {:?}", err);
+                AppError::BusinessError("邮件发送失败")
+            })?;
+
+            self.transport.send(message).await.map_err(|err| {
+                log::error!("send mail to: {} error: {:?}", mail.to, err);
+                AppError::BusinessError("邮件发送失败")
+            })?;
+            Ok(())
+        })
+    }
+}
+
+/*
+ *邮件写入本地文件并打印日志(本地开发测试使用)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub struct FileMailSender {
+    dir: PathBuf,
+}
+
+impl FileMailSender {
+    pub fn new(dir: &str) -> FileMailSender {
+        FileMailSender { dir: PathBuf::from(dir) }
+    }
+}
+
+impl MailSender for FileMailSender {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, AppResult<()>> {
+        Box::pin(async move {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_millis();
+            let path = self.dir.join(format!("{}.eml", now));
+            let content = format!("To: {}\r\nSubject: {}\r\n\r\n{}\r\n", mail.to, mail.subject, mail.body);
+
+            fs::create_dir_all(&self.dir).and_then(|_| fs::write(&path, content)).map_err(|err| {
+                log::error!("write mail file: {:?} error: {:?}", path, err);
+                AppError::BusinessError("邮件发送失败")
+            })?;
+            log::info!("mail to: {}, subject: {}, saved to: {:?}", mail.to, mail.subject, path);
+            Ok(())
+        })
+    }
+}
+
+fn create_mail_sender(config: &MailConfig) -> Box<dyn MailSender> {
+    if config.sender != "smtp" {
+        return Box::new(FileMailSender::new(&config.file_dir));
+    }
+
+    let (host, port, tls) = (&config.smtp_host, config.smtp_port, &config.smtp_tls);
+    match SmtpMailSender::new(host, port, tls, &config.smtp_username, &config.smtp_password, config.from_address()) {
+        Ok(sender) => Box::new(sender),
+        Err(err) => {
+            log::error!("create smtp mail sender error: {}, use file mail sender", err);
+            Box::new(FileMailSender::new(&config.file_dir))
+        }
+    }
+}
+
+/*
+ *发送邮件
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn send_mail(to: &str, subject: &str, body: &str) -> AppResult<()> {
+    let mail = Mail {
+        to: to.to_string(),
+        subject: subject.to_string(),
+        body: body.to_string(),
+    };
+    MAIL_SENDER.send(mail).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::mail_util::{FileMailSender, Mail, MailSender};
+    use std::fs;
+
+    #[test]
+    fn test_file_mail_sender() {
+        let dir = std::env::temp_dir().join("ntex-admin-mail-test");
+        let _ = fs::remove_dir_all(&dir);
+        let sender = FileMailSender::new(dir.to_str().unwrap());
+        let mail = Mail {
+            to: "admin@qq.com".to_string(),
+            subject: "重置密码".to_string(),
+            body: "token".to_string(),
+        };
+        futures::executor::block_on(sender.send(mail)).unwrap();
+
+        let files = fs::read_dir(&dir).unwrap().map(|x| x.unwrap().path()).collect::<Vec<_>>();
+        assert_eq!(files.len(), 1);
+        assert!(fs::read_to_string(&files[0]).unwrap().contains("Subject: 重置密码"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::common::config::APP_CONFIG;
+use log::{LevelFilter, Log, Metadata, Record};
+use log4rs::config::RawConfig;
+use regex::Regex;
+use std::time::Duration;
+
+const MASK: &str = "******";
+
+lazy_static! {
+    // 脱敏字段(字段名包含其中任意一个即脱敏,不区分大小写),在config.toml的[log] mask_fields中配置,多个用逗号分隔
+    pub static ref MASKER: Masker = Masker::new(&APP_CONFIG.log.mask_fields);
+}
+
+/*
+ *敏感字段脱敏,同时支持Debug输出(password: "123")和json(\"password\":\"123\")
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub struct Masker {
+    regex: Option<Regex>,
+}
+
+impl Masker {
+    pub fn new(fields: &str) -> Masker {
+        let fields = fields.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()).map(regex::escape).collect::<Vec<String>>();
+        if fields.is_empty() {
+            return Masker { regex: None };
+        }
+
+        let pattern = format!(r#"(?i)(\w*(?:{})\w*"?\s*[:=]\s*(?:Some\()?")(?:[^"\\]|\\.)*(")"#, fields.join("|"));
+        Masker {
+            regex: Some(Regex::new(&pattern).expect("脱敏字段配置不正确")),
+        }
+    }
+
+    pub fn mask(&self, s: &str) -> String {
+        match &self.regex {
+            None => s.to_string(),
+            Some(regex) => regex.replace_all(s, format!("${{1}}{}${{2}}", MASK)).to_string(),
+        }
+    }
+}
+
+/*
+ *对日志内容脱敏后再交给log4rs输出
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+struct MaskLogger {
+    inner: log4rs::Logger,
+}
+
+impl Log for MaskLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.inner.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = MASKER.mask(&record.args().to_string());
+        self.inner.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
+
+/*
+ *初始化日志(log4rs配置文件)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn init_log(path: &str) {
+    let config = log4rs::config::load_config_file(path, Default::default()).expect("加载日志配置失败");
+    let logger = log4rs::Logger::new(config);
+    let level: LevelFilter = logger.max_log_level();
+    let handle = logger.handle();
+
+    log::set_boxed_logger(Box::new(MaskLogger { inner: logger })).expect("初始化日志失败");
+    log::set_max_level(level);
+
+    // MaskLogger包装了log4rs的Logger,不能使用log4rs::init_file自带的刷新,按refresh_rate自行检查配置文件的修改
+    if let Some(refresh_rate) = read_refresh_rate(path) {
+        let path = path.to_string();
+        std::thread::spawn(move || watch_log_config(&path, refresh_rate, handle));
+    }
+}
+
+// 读取日志配置中的refresh_rate,未配置时不刷新
+fn read_refresh_rate(path: &str) -> Option<Duration> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_yaml::from_str::<RawConfig>(&content).ok()?.refresh_rate()
+}
+
+// 配置文件修改后重新加载,加载失败时保留原配置
+fn watch_log_config(path: &str, refresh_rate: Duration, handle: log4rs::Handle) {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|x| x.modified()).ok();
+    let mut last_modified = modified(path);
+
+    loop {
+        std::thread::sleep(refresh_rate);
+        let current = modified(path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match log4rs::config::load_config_file(path, Default::default()) {
+            Ok(config) => {
+                handle.set_config(config);
+                log::info!("重新加载日志配置: {}", path);
+            }
+            Err(err) => log::error!("重新加载日志配置失败: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::mask_util::Masker;
+
+    #[test]
+    fn test_mask_debug() {
+        let masker = Masker::new("password,token,mobile");
+        assert_eq!(
+            masker.mask(r#"UserLoginReq { account: "admin", password: "123456" }"#),
+            r#"UserLoginReq { account: "admin", password: "******" }"#
+        );
+        assert_eq!(
+            masker.mask(r#"Req { mobile: Some("18613030111"), refresh_token: "a\"b" }"#),
+            r#"Req { mobile: Some("******"), refresh_token: "******" }"#
+        );
+    }
+
+    #[test]
+    fn test_mask_json() {
+        let masker = Masker::new("password, token");
+        assert_eq!(
+            masker.mask(r#"{"userName":"admin","rePassword":"123","refreshToken": "abc"}"#),
+            r#"{"userName":"admin","rePassword":"******","refreshToken": "******"}"#
+        );
+        assert_eq!(masker.mask(r#"{"id":1,"status":0}"#), r#"{"id":1,"status":0}"#);
+    }
+
+    #[test]
+    fn test_mask_empty_fields() {
+        let masker = Masker::new("");
+        assert_eq!(masker.mask(r#"{"password":"123"}"#), r#"{"password":"123"}"#);
+    }
+}
@@ -0,0 +1,125 @@
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+
+// 请求耗时直方图的桶(秒)
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+lazy_static! {
+    static ref REGISTRY: Registry = Registry::new_custom(Some("ntex_admin".to_string()), None).expect("create metrics registry error");
+    static ref HTTP_REQUESTS: IntCounterVec = register(IntCounterVec::new(Opts::new("http_requests_total", "HTTP请求数"), &["method", "route", "status"]));
+    static ref HTTP_REQUEST_DURATION: HistogramVec = register(HistogramVec::new(
+        HistogramOpts::new("http_request_duration_seconds", "HTTP请求耗时(秒)").buckets(LATENCY_BUCKETS.to_vec()),
+        &["method", "route", "status"]
+    ));
+    static ref AUTH_RESULTS: IntCounterVec = register(IntCounterVec::new(Opts::new("auth_requests_total", "JwtAuth鉴权结果"), &["result"]));
+    static ref LOGIN_RESULTS: IntCounterVec = register(IntCounterVec::new(Opts::new("login_total", "登录结果"), &["result"]));
+    static ref DB_POOL: IntGaugeVec = register(IntGaugeVec::new(Opts::new("db_pool_connections", "数据库连接池状态"), &["state"]));
+}
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(collector: Result<T, prometheus::Error>) -> T {
+    let collector = collector.expect("create metrics collector error");
+    REGISTRY.register(Box::new(collector.clone())).expect("register metrics collector error");
+    collector
+}
+
+/*
+ *JwtAuth鉴权结果
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone, Copy)]
+pub enum AuthResult {
+    Allowed,      //通过
+    Denied,       //无权限
+    InvalidToken, //令牌为空、无效、过期或者已失效
+    Error,        //查询权限失败
+}
+
+impl AuthResult {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuthResult::Allowed => "allowed",
+            AuthResult::Denied => "denied",
+            AuthResult::InvalidToken => "invalid_token",
+            AuthResult::Error => "error",
+        }
+    }
+}
+
+/*
+ *记录HTTP请求(route为匹配的接口路径,没有匹配的接口统一记为unmatched,避免标签数量无限增长)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn record_request(method: &str, route: &str, status: u16, secs: f64) {
+    let status = status.to_string();
+    let labels = [method, route, status.as_str()];
+    HTTP_REQUESTS.with_label_values(&labels).inc();
+    HTTP_REQUEST_DURATION.with_label_values(&labels).observe(secs);
+}
+
+/*
+ *记录JwtAuth鉴权结果
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn record_auth(result: AuthResult) {
+    AUTH_RESULTS.with_label_values(&[result.as_str()]).inc();
+}
+
+/*
+ *记录登录结果
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn record_login(success: bool) {
+    LOGIN_RESULTS.with_label_values(&[if success { "success" } else { "failure" }]).inc();
+}
+
+/*
+ *更新数据库连接池状态(state为连接池状态中的数值字段,例如max_open、connections、in_use、idle)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn set_db_pool_state(state: &serde_json::Value) {
+    if let Some(map) = state.as_object() {
+        for (key, value) in map {
+            if let Some(value) = value.as_i64() {
+                DB_POOL.with_label_values(&[key.as_str()]).set(value);
+            }
+        }
+    }
+}
+
+/*
+ *导出Prometheus文本格式的指标
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn gather() -> String {
+    let mut buffer = Vec::new();
+    if let Err(err) = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer) {
+        log::error!("encode metrics error: {}", err);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::metrics_util::{gather, record_auth, record_login, record_request, set_db_pool_state, AuthResult};
+
+    #[test]
+    fn test_gather() {
+        record_request("POST", "/api/system/user/login", 200, 0.02);
+        record_auth(AuthResult::Denied);
+        record_login(false);
+        set_db_pool_state(&serde_json::json!({"max_open": 32, "in_use": 1, "driver": "mysql"}));
+
+        let text = gather();
+        assert!(text.contains(r#"ntex_admin_http_requests_total{method="POST",route="/api/system/user/login",status="200"} 1"#));
+        assert!(text.contains(r#"ntex_admin_http_request_duration_seconds_bucket{method="POST",route="/api/system/user/login",status="200",le="0.025"} 1"#));
+        assert!(text.contains(r#"ntex_admin_auth_requests_total{result="denied"} 1"#));
+        assert!(text.contains(r#"ntex_admin_login_total{result="failure"} 1"#));
+        assert!(text.contains(r#"ntex_admin_db_pool_connections{state="max_open"} 32"#));
+        assert!(!text.contains("driver"));
+    }
+}
@@ -0,0 +1,355 @@
+use crate::common::error::{AppError, AppResult};
+use crate::model::system::sys_schema_migration_model::SchemaMigration;
+use crate::utils::token_util::sha256_hex;
+use rbatis::executor::RBatisConnExecutor;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::RBatis;
+use rbs::value;
+
+// 迁移锁名称,多个实例同时启动时只有一个执行迁移
+const MIGRATION_LOCK: &str = "ntex_admin_schema_migrations";
+// 等待迁移锁的时间(秒)
+const MIGRATION_LOCK_TIMEOUT_SECS: i64 = 60;
+
+/*
+ *数据库迁移(脚本编译进程序,版本号只能递增,已发布的脚本不能修改)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,       //版本号
+    pub name: &'static str, //名称
+    pub sql: &'static str,  //脚本内容
+}
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        sha256_hex(self.sql)
+    }
+}
+
+// 新增迁移: 在migrations目录下添加V{版本号}__{名称}.sql,然后追加到这里
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        sql: include_str!("../../migrations/V001__baseline.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "user_password_hash",
+        sql: include_str!("../../migrations/V002__user_password_hash.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "user_token",
+        sql: include_str!("../../migrations/V003__user_token.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "user_mfa",
+        sql: include_str!("../../migrations/V004__user_mfa.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "user_password_history",
+        sql: include_str!("../../migrations/V005__user_password_history.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "drop_soft_delete_unique",
+        sql: include_str!("../../migrations/V006__drop_soft_delete_unique.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "security_menus",
+        sql: include_str!("../../migrations/V007__security_menus.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "backfill_pwd_update_date",
+        sql: include_str!("../../migrations/V008__backfill_pwd_update_date.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "live_unique_keys",
+        sql: include_str!("../../migrations/V009__live_unique_keys.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "user_login_fail",
+        sql: include_str!("../../migrations/V010__user_login_fail.sql"),
+    },
+];
+
+/*
+ *迁移状态
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub current: i64,                     //数据库当前版本
+    pub latest: i64,                      //程序支持的最新版本
+    pub pending: Vec<&'static Migration>, //待执行的迁移
+}
+
+/*
+ *启动时检查数据库版本: auto_migrate为true时执行待执行的迁移,数据库版本高于程序时拒绝启动
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn migrate_on_startup(rb: &RBatis, auto_migrate: bool) -> AppResult<()> {
+    if auto_migrate {
+        let applied = migrate(rb).await?;
+        log::info!("schema migrations applied: {}", applied);
+        return Ok(());
+    }
+
+    let status = migration_status(rb).await?;
+    if !status.pending.is_empty() {
+        log::warn!(
+            "schema version {} is behind {}, run `migrate` to apply {} pending migration(s)",
+            status.current,
+            status.latest,
+            status.pending.len()
+        );
+    }
+    Ok(())
+}
+
+/*
+ *查询迁移状态(数据库版本高于程序或者已执行的脚本被修改时返回错误)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn migration_status(rb: &RBatis) -> AppResult<MigrationStatus> {
+    create_migration_table(rb).await?;
+    let applied = SchemaMigration::select_applied(rb).await?;
+    check_applied(MIGRATIONS, &applied)
+}
+
+/*
+ *执行待执行的迁移,返回执行的数量
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn migrate(rb: &RBatis) -> AppResult<usize> {
+    create_migration_table(rb).await?;
+
+    // 锁和迁移使用同一个连接,锁在连接上生效
+    let conn = rb.acquire().await?;
+    let locked: Option<i64> = conn.query_decode("select get_lock(?, ?)", vec![value!(MIGRATION_LOCK), value!(MIGRATION_LOCK_TIMEOUT_SECS)]).await?;
+    if locked != Some(1) {
+        return Err(AppError::MigrationError("等待迁移锁超时,可能有其他实例正在执行迁移".to_string()));
+    }
+
+    let result: AppResult<usize> = async {
+        let mut applied = SchemaMigration::select_applied(&conn).await?;
+
+        // 已有数据库(手动导入过sql脚本)没有迁移记录时,把基线版本标记为已执行
+        if applied.is_empty() && table_exists(&conn, "sys_user").await? {
+            let baseline = &MIGRATIONS[0];
+            log::info!("existing schema found, mark migration {} {} as applied", baseline.version, baseline.name);
+            SchemaMigration::insert(&conn, &record(baseline)).await?;
+            applied = SchemaMigration::select_applied(&conn).await?;
+        }
+
+        let status = check_applied(MIGRATIONS, &applied)?;
+        for migration in &status.pending {
+            log::info!("apply migration {} {}", migration.version, migration.name);
+            for sql in split_statements(migration.sql) {
+                conn.exec(&sql, vec![]).await.map_err(|err| {
+                    // mysql的ddl不支持回滚,执行失败需要人工处理后重新执行
+                    AppError::MigrationError(format!("执行迁移 {} {} 失败: {}, sql: {}", migration.version, migration.name, err, sql))
+                })?;
+            }
+            SchemaMigration::insert(&conn, &record(migration)).await?;
+        }
+        Ok(status.pending.len())
+    }
+    .await;
+
+    if let Err(err) = conn.exec("select release_lock(?)", vec![value!(MIGRATION_LOCK)]).await {
+        log::error!("release migration lock error: {}", err);
+    }
+    result
+}
+
+async fn create_migration_table(rb: &RBatis) -> AppResult<()> {
+    let sql = "create table if not exists schema_migrations
+(
+    version      bigint                             not null comment '版本号'
+        primary key,
+    name         varchar(100)                       not null comment '名称',
+    checksum     char(64)                           not null comment '脚本sha256',
+    applied_time datetime default CURRENT_TIMESTAMP not null comment '执行时间'
+) comment = '数据库迁移记录'";
+    rb.exec(sql, vec![]).await?;
+    Ok(())
+}
+
+async fn table_exists(rb: &RBatisConnExecutor, table: &str) -> AppResult<bool> {
+    let count: i64 = rb
+        .query_decode("select count(1) from information_schema.tables where table_schema = database() and table_name = ?", vec![value!(table)])
+        .await?;
+    Ok(count > 0)
+}
+
+fn record(migration: &Migration) -> SchemaMigration {
+    SchemaMigration {
+        version: migration.version,
+        name: migration.name.to_string(),
+        checksum: migration.checksum(),
+        applied_time: Some(DateTime::now()),
+    }
+}
+
+/*
+ *对比已执行的迁移和程序中的迁移,返回待执行的迁移
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn check_applied(migrations: &'static [Migration], applied: &[SchemaMigration]) -> AppResult<MigrationStatus> {
+    let latest = migrations.iter().map(|x| x.version).max().unwrap_or_default();
+    let current = applied.iter().map(|x| x.version).max().unwrap_or_default();
+
+    if current > latest {
+        return Err(AppError::MigrationError(format!("数据库版本 {} 高于程序支持的版本 {},请升级程序", current, latest)));
+    }
+
+    for x in applied {
+        match migrations.iter().find(|m| m.version == x.version) {
+            None => return Err(AppError::MigrationError(format!("数据库中的迁移 {} {} 在程序中不存在", x.version, x.name))),
+            Some(m) if m.checksum() != x.checksum => {
+                return Err(AppError::MigrationError(format!("迁移 {} {} 的脚本已被修改(checksum不一致)", x.version, x.name)));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut pending = migrations.iter().filter(|m| !applied.iter().any(|x| x.version == m.version)).collect::<Vec<_>>();
+    pending.sort_by_key(|m| m.version);
+    Ok(MigrationStatus { current, latest, pending })
+}
+
+/*
+ *把脚本拆分成单条语句(忽略注释,引号内的分号不拆分)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            current.push(c);
+            if c == '\\' {
+                if let Some(x) = chars.next() {
+                    current.push(x);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' | '`' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for x in chars.by_ref() {
+                    if x == '\n' {
+                        current.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for x in chars.by_ref() {
+                    if prev == '*' && x == '/' {
+                        break;
+                    }
+                    prev = x;
+                }
+            }
+            ';' => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::system::sys_schema_migration_model::SchemaMigration;
+    use crate::utils::migration_util::{check_applied, split_statements, Migration, MIGRATIONS};
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "-- 用户表\ncreate table t (name varchar(10) comment '名称;备注');\n\n/* 初始化 */ insert into t values ('a--b');\ninsert into t values ('it\\'s')";
+        assert_eq!(
+            split_statements(sql),
+            vec![
+                "create table t (name varchar(10) comment '名称;备注')",
+                "insert into t values ('a--b')",
+                "insert into t values ('it\\'s')"
+            ]
+        );
+
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as i64 + 1);
+            let statements = split_statements(migration.sql);
+            assert!(!statements.is_empty());
+            assert!(statements.iter().all(|x| x.matches("create table").count() + x.matches("INSERT INTO").count() <= 1));
+        }
+    }
+
+    #[test]
+    fn test_check_applied() {
+        static TEST_MIGRATIONS: &[Migration] = &[
+            Migration {
+                version: 1,
+                name: "baseline",
+                sql: "create table a (id int)",
+            },
+            Migration {
+                version: 2,
+                name: "add_b",
+                sql: "create table b (id int)",
+            },
+        ];
+        let record = |version: i64, checksum: String| SchemaMigration {
+            version,
+            name: "".to_string(),
+            checksum,
+            applied_time: None,
+        };
+
+        let status = check_applied(TEST_MIGRATIONS, &[]).unwrap();
+        assert_eq!((status.current, status.latest, status.pending.len()), (0, 2, 2));
+
+        let status = check_applied(TEST_MIGRATIONS, &[record(1, TEST_MIGRATIONS[0].checksum())]).unwrap();
+        assert_eq!(status.pending.iter().map(|x| x.version).collect::<Vec<_>>(), vec![2]);
+
+        assert!(check_applied(TEST_MIGRATIONS, &[record(1, "x".to_string())]).is_err());
+        assert!(check_applied(TEST_MIGRATIONS, &[record(3, "x".to_string())]).unwrap_err().to_string().contains("高于"));
+    }
+}
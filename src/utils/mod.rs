@@ -1,3 +1,24 @@
+pub mod captcha_util;
+pub mod data_scope_util;
+pub mod ip_region_util;
+pub mod ip_util;
 pub mod jwt_util;
+pub mod login_lock_util;
+pub mod mail_util;
+pub mod mask_util;
+pub mod metrics_util;
+pub mod migration_util;
+pub mod password_policy_util;
+pub mod password_util;
+pub mod path_pattern_util;
+pub mod permission_util;
+pub mod png_util;
+pub mod reset_token_util;
+pub mod revoke_util;
+pub mod session_util;
 pub mod time_util;
+pub mod token_util;
+pub mod totp_util;
+pub mod two_factor_util;
+pub mod tx_util;
 pub mod user_agent_util;
@@ -0,0 +1,151 @@
+use crate::common::config::{PasswordConfig, APP_CONFIG};
+use crate::utils::token_util::random_token;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 密码过期修改令牌有效期(秒)
+pub const CHANGE_TOKEN_EXPIRE_SECS: u64 = 300;
+
+lazy_static! {
+    pub static ref PASSWORD_POLICY: PasswordPolicy = PasswordPolicy::from_config(&APP_CONFIG.password);
+    // 密码过期修改令牌: 令牌 -> (用户ID, 过期时间)
+    static ref PASSWORD_CHANGE_TOKEN: RwLock<HashMap<String, (i64, u64)>> = RwLock::new(HashMap::new());
+}
+
+/*
+ *密码策略
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,     //最小长度
+    pub min_classes: usize,    //至少包含的字符种类数(大写字母,小写字母,数字,特殊字符)
+    pub history_count: usize,  //不能与最近N次使用过的密码相同(0表示不限制)
+    pub max_age_days: u64,     //密码有效期(天,0表示永不过期)
+    pub check_user_name: bool, //密码不能包含登录账号
+}
+
+impl PasswordPolicy {
+    pub fn from_config(config: &PasswordConfig) -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: config.min_length,
+            min_classes: config.min_classes,
+            history_count: config.history_count,
+            max_age_days: config.max_age_days,
+            check_user_name: config.check_user_name,
+        }
+    }
+
+    /*
+     *校验密码强度(不包括历史密码),不符合时返回原因
+     *author：刘飞华
+     *date：2025/01/10 09:21:35
+     */
+    pub fn validate(&self, user_name: &str, password: &str) -> Result<(), String> {
+        if password.chars().count() < self.min_length {
+            return Err(format!("密码长度不能少于{}位", self.min_length));
+        }
+
+        let classes = [
+            password.chars().any(|x| x.is_ascii_uppercase()),
+            password.chars().any(|x| x.is_ascii_lowercase()),
+            password.chars().any(|x| x.is_ascii_digit()),
+            password.chars().any(|x| !x.is_ascii_alphanumeric()),
+        ];
+        if classes.iter().filter(|x| **x).count() < self.min_classes {
+            return Err(format!("密码至少包含大写字母,小写字母,数字,特殊字符中的{}种", self.min_classes));
+        }
+
+        let user_name = user_name.trim().to_lowercase();
+        if self.check_user_name && !user_name.is_empty() && password.to_lowercase().contains(&user_name) {
+            return Err("密码不能包含登录账号".to_string());
+        }
+
+        Ok(())
+    }
+
+    /*
+     *密码是否过期(last_change为密码最后修改时间的时间戳,单位秒)
+     *author：刘飞华
+     *date：2025/01/10 09:21:35
+     */
+    pub fn is_expired(&self, last_change: Option<i64>, now: i64) -> bool {
+        match last_change {
+            Some(x) if self.max_age_days > 0 => now - x > (self.max_age_days * 24 * 60 * 60) as i64,
+            _ => false,
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs()
+}
+
+/*
+ *创建密码过期修改令牌(登录时密码已过期,只能用该令牌修改密码)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn create_change_token(user_id: i64) -> String {
+    let token = random_token();
+    let now = now_secs();
+    let mut tokens = PASSWORD_CHANGE_TOKEN.write().unwrap();
+    tokens.retain(|_, (_, expire)| *expire > now);
+    tokens.insert(token.clone(), (user_id, now + CHANGE_TOKEN_EXPIRE_SECS));
+    token
+}
+
+/*
+ *查询密码过期修改令牌对应的用户ID(已过期返回None)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn get_change_token(token: &str) -> Option<i64> {
+    PASSWORD_CHANGE_TOKEN.read().unwrap().get(token).filter(|(_, expire)| *expire > now_secs()).map(|(user_id, _)| *user_id)
+}
+
+/*
+ *移除密码过期修改令牌(修改成功后只能使用一次)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn remove_change_token(token: &str) {
+    PASSWORD_CHANGE_TOKEN.write().unwrap().remove(token);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::password_policy_util::PasswordPolicy;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            min_classes: 3,
+            history_count: 5,
+            max_age_days: 90,
+            check_user_name: true,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let policy = policy();
+        assert!(policy.validate("admin", "Ab1!").is_err());
+        assert!(policy.validate("admin", "abcdefgh1").is_err());
+        assert!(policy.validate("admin", "xAdmin#2025").is_err());
+        assert!(policy.validate("admin", "Koobe#2025").is_ok());
+        assert!(policy.validate("admin", "koobe#2025").is_ok());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let policy = policy();
+        let day = 24 * 60 * 60;
+        assert!(!policy.is_expired(None, 100 * day));
+        assert!(!policy.is_expired(Some(day), 90 * day));
+        assert!(policy.is_expired(Some(day), 92 * day));
+        assert!(!PasswordPolicy { max_age_days: 0, ..policy }.is_expired(Some(0), 1000 * day));
+    }
+}
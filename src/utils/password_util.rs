@@ -0,0 +1,71 @@
+use crate::common::error::{AppError, AppResult};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+// 密码哈希前缀(PHC格式,包含算法标识,后面紧跟版本号 v=19)
+const HASH_PREFIX: &str = "$argon2id$";
+
+/*
+ *生成密码哈希(argon2id)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn hash_password(password: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Ok(hash.to_string()),
+        Err(err) => {
+            log::error!("hash password error: {:?}", err);
+            Err(AppError::BusinessError("密码加密失败"))
+        }
+    }
+}
+
+/*
+ *校验密码,兼容历史明文密码
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    if !is_hashed(stored) {
+        return password == stored;
+    }
+
+    match PasswordHash::new(stored) {
+        Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(err) => {
+            log::error!("parse password hash error: {:?}", err);
+            false
+        }
+    }
+}
+
+/*
+ *判断密码是否已经哈希(历史明文密码需要在登录成功后升级)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn is_hashed(stored: &str) -> bool {
+    stored.starts_with(HASH_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::password_util::{hash_password, is_hashed, verify_password};
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash_password("123456").unwrap();
+        assert!(is_hashed(&hash));
+        assert!(verify_password("123456", &hash));
+        assert!(!verify_password("654321", &hash));
+    }
+
+    #[test]
+    fn test_verify_plaintext() {
+        assert!(!is_hashed("123456"));
+        assert!(verify_password("123456", "123456"));
+        assert!(!verify_password("654321", "123456"));
+    }
+}
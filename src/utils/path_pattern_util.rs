@@ -0,0 +1,173 @@
+// 接口权限规则(对应sys_menu.api_url)
+// 格式: [!][METHOD[,METHOD] ]pattern
+//   !            拒绝规则,优先于允许规则
+//   METHOD       请求方式,如 GET、POST,不写表示所有请求方式
+//   pattern      路径模式,按 / 分段匹配:
+//                  {id}   匹配一个路径参数
+//                  *      匹配一段(段内也可以使用 * 做前后缀匹配,如 query*)
+//                  **     匹配剩余的任意多段
+// 例如: /api/system/user/*、GET /api/system/user/{id}、!POST /api/system/user/deleteUser
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionRule {
+    pub deny: bool,           //是否为拒绝规则
+    pub methods: Vec<String>, //请求方式(为空表示所有)
+    segments: Vec<String>,    //路径分段
+}
+
+impl PermissionRule {
+    pub fn parse(api_url: &str) -> Option<PermissionRule> {
+        let mut rule = api_url.trim();
+        let deny = rule.starts_with('!');
+        if deny {
+            rule = rule[1..].trim_start();
+        }
+
+        let (methods, pattern): (Vec<String>, &str) = match rule.split_once(char::is_whitespace) {
+            Some((methods, pattern)) => (methods.split(',').map(|x| x.trim().to_uppercase()).filter(|x| !x.is_empty() && x != "*").collect(), pattern.trim()),
+            None => (Vec::new(), rule),
+        };
+
+        if !pattern.starts_with('/') {
+            return None;
+        }
+
+        let segments = split_path(pattern).map(|x| x.to_string()).collect::<Vec<String>>();
+        for (i, x) in segments.iter().enumerate() {
+            if x == "**" && i != segments.len() - 1 {
+                return None;
+            }
+            if (x.contains('{') || x.contains('}')) && !(x.starts_with('{') && x.ends_with('}') && x.len() > 2) {
+                return None;
+            }
+        }
+
+        Some(PermissionRule { deny, methods, segments })
+    }
+
+    pub fn is_match(&self, method: &str, path: &str) -> bool {
+        if !self.methods.is_empty() && !self.methods.iter().any(|x| x.eq_ignore_ascii_case(method)) {
+            return false;
+        }
+
+        let path = split_path(path).collect::<Vec<&str>>();
+        match_segments(&self.segments, &path)
+    }
+}
+
+/*
+ *判断是否有权限访问(拒绝优先于允许)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn check_permission(rules: &[PermissionRule], method: &str, path: &str) -> bool {
+    let mut allowed = false;
+    for rule in rules {
+        if rule.is_match(method, path) {
+            if rule.deny {
+                return false;
+            }
+            allowed = true;
+        }
+    }
+    allowed
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|x| !x.is_empty())
+}
+
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(x) if x == "**" => true,
+        Some(x) => match path.first() {
+            None => false,
+            Some(segment) => match_segment(x, segment) && match_segments(&pattern[1..], &path[1..]),
+        },
+    }
+}
+
+fn match_segment(pattern: &str, segment: &str) -> bool {
+    if pattern.starts_with('{') && pattern.ends_with('}') {
+        return !segment.is_empty();
+    }
+    if !pattern.contains('*') {
+        return pattern == segment;
+    }
+
+    // 段内通配: 依次匹配 * 之间的各部分
+    let parts = pattern.split('*').collect::<Vec<&str>>();
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !segment.starts_with(first) || segment.len() < first.len() + last.len() || !segment.ends_with(last) {
+        return false;
+    }
+
+    let mut rest = &segment[first.len()..segment.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            None => return false,
+            Some(i) => rest = &rest[i + part.len()..],
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::path_pattern_util::{check_permission, PermissionRule};
+
+    fn rules(api_urls: &[&str]) -> Vec<PermissionRule> {
+        api_urls.iter().map(|x| PermissionRule::parse(x).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_exact() {
+        let rules = rules(&["/api/system/user/addUser"]);
+        assert!(check_permission(&rules, "POST", "/api/system/user/addUser"));
+        assert!(!check_permission(&rules, "POST", "/api/system/user/deleteUser"));
+        assert!(!check_permission(&rules, "POST", "/api/system/user/addUser/1"));
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let rules = rules(&["/api/system/user/*", "/api/system/role/query*", "/api/system/dept/**"]);
+        assert!(check_permission(&rules, "POST", "/api/system/user/addUser"));
+        assert!(!check_permission(&rules, "POST", "/api/system/user/2fa/setup"));
+        assert!(check_permission(&rules, "POST", "/api/system/role/queryRoleList"));
+        assert!(!check_permission(&rules, "POST", "/api/system/role/addRole"));
+        assert!(check_permission(&rules, "POST", "/api/system/dept"));
+        assert!(check_permission(&rules, "POST", "/api/system/dept/a/b/c"));
+    }
+
+    #[test]
+    fn test_path_param() {
+        let rules = rules(&["/api/system/user/{id}/detail"]);
+        assert!(check_permission(&rules, "GET", "/api/system/user/12/detail"));
+        assert!(!check_permission(&rules, "GET", "/api/system/user/detail"));
+    }
+
+    #[test]
+    fn test_method() {
+        let rules = rules(&["GET,POST /api/system/notice/*"]);
+        assert!(check_permission(&rules, "get", "/api/system/notice/queryNoticeList"));
+        assert!(!check_permission(&rules, "DELETE", "/api/system/notice/queryNoticeList"));
+    }
+
+    #[test]
+    fn test_deny_over_allow() {
+        let rules = rules(&["!POST /api/system/user/deleteUser", "/api/system/user/*"]);
+        assert!(check_permission(&rules, "POST", "/api/system/user/addUser"));
+        assert!(!check_permission(&rules, "POST", "/api/system/user/deleteUser"));
+        assert!(check_permission(&rules, "GET", "/api/system/user/deleteUser"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(PermissionRule::parse("").is_none());
+        assert!(PermissionRule::parse("api/system/user").is_none());
+        assert!(PermissionRule::parse("/api/**/user").is_none());
+        assert!(PermissionRule::parse("/api/system/user/{id").is_none());
+        assert!(PermissionRule::parse("/api/system/user/{}").is_none());
+    }
+}
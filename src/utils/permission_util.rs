@@ -0,0 +1,137 @@
+use crate::common::error::AppResult;
+use crate::model::system::sys_menu_model::{select_api_url_by_user_id, Menu};
+use crate::model::system::sys_user_role_model::is_admin;
+use crate::utils::path_pattern_util::PermissionRule;
+use crate::RB;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 缓存的用户权限: (加载时的权限版本号, 接口权限规则)
+type CachedPermissions = (u64, Arc<Vec<PermissionRule>>);
+
+lazy_static! {
+    // 权限版本号(启动时间初始化,保证重启后与旧令牌中的版本号不同)
+    static ref PERMISSION_VERSION: AtomicU64 = AtomicU64::new(SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs());
+    // 用户权限缓存: user_id -> 权限
+    static ref PERMISSION_CACHE: RwLock<HashMap<i64, CachedPermissions>> = RwLock::new(HashMap::new());
+}
+
+/*
+ *当前权限版本号
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn permission_version() -> u64 {
+    PERMISSION_VERSION.load(Ordering::SeqCst)
+}
+
+/*
+ *权限数据变更后清空缓存(角色菜单、用户角色、菜单变更时调用)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn invalidate_permissions() {
+    PERMISSION_VERSION.fetch_add(1, Ordering::SeqCst);
+    PERMISSION_CACHE.write().unwrap().clear();
+}
+
+/*
+ *查询用户接口权限(优先从缓存读取)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn get_permissions(user_id: i64) -> AppResult<Arc<Vec<PermissionRule>>> {
+    let version = permission_version();
+    if let Some((v, permissions)) = PERMISSION_CACHE.read().unwrap().get(&user_id) {
+        if *v == version {
+            return Ok(permissions.clone());
+        }
+    }
+
+    let permissions = Arc::new(load_permissions(user_id).await?);
+
+    //加载期间权限发生变更时不写入缓存,避免缓存旧数据
+    let mut cache = PERMISSION_CACHE.write().unwrap();
+    if version == permission_version() {
+        cache.insert(user_id, (version, permissions.clone()));
+    }
+    Ok(permissions)
+}
+
+/*
+ *从数据库查询用户接口权限(超级管理员拥有所有菜单的允许规则)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+async fn load_permissions(user_id: i64) -> AppResult<Vec<PermissionRule>> {
+    let rb = &mut RB.clone();
+
+    let mut role_urls: Vec<String> = Vec::new();
+    for x in select_api_url_by_user_id(rb, &user_id).await? {
+        if let Some(api_url) = x.get("api_url") {
+            role_urls.push(api_url.to_string());
+        }
+    }
+
+    let mut admin_urls: Vec<String> = Vec::new();
+    if is_admin(rb, &user_id).await? == 1 {
+        admin_urls = Menu::select_all(rb).await?.into_iter().filter_map(|x| x.api_url).collect();
+        log::info!("load admin permissions: {:?}", user_id);
+    } else {
+        log::info!("load ordinary permissions: {:?}", user_id);
+    }
+
+    Ok(build_permissions(&role_urls, &admin_urls))
+}
+
+/*
+ *解析接口权限规则(超级管理员额外获得所有菜单的允许规则,拒绝规则只来自用户自己的角色)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn build_permissions(role_urls: &[String], admin_urls: &[String]) -> Vec<PermissionRule> {
+    let mut permissions: Vec<PermissionRule> = Vec::new();
+    for (api_url, from_role) in role_urls.iter().map(|x| (x, true)).chain(admin_urls.iter().map(|x| (x, false))) {
+        if api_url.trim().is_empty() {
+            continue;
+        }
+        match PermissionRule::parse(api_url) {
+            Some(rule) if rule.deny && !from_role => {}
+            Some(rule) => permissions.push(rule),
+            None => log::warn!("invalid api_url permission pattern: {:?}", api_url),
+        }
+    }
+
+    permissions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::path_pattern_util::check_permission;
+    use crate::utils::permission_util::build_permissions;
+
+    fn urls(api_urls: &[&str]) -> Vec<String> {
+        api_urls.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_admin_skip_deny_rules() {
+        let all = urls(&["/api/system/user/*", "!POST /api/system/user/deleteUser", "/api/system/role/*", ""]);
+
+        //超级管理员不受其他角色菜单中的拒绝规则影响
+        let admin = build_permissions(&[], &all);
+        assert!(check_permission(&admin, "POST", "/api/system/user/deleteUser"));
+        assert!(check_permission(&admin, "POST", "/api/system/role/addRole"));
+
+        //用户自己角色中的拒绝规则仍然生效
+        let admin = build_permissions(&urls(&["!POST /api/system/role/deleteRole"]), &all);
+        assert!(!check_permission(&admin, "POST", "/api/system/role/deleteRole"));
+        assert!(check_permission(&admin, "POST", "/api/system/role/addRole"));
+
+        let ordinary = build_permissions(&all, &[]);
+        assert!(!check_permission(&ordinary, "POST", "/api/system/user/deleteUser"));
+        assert!(check_permission(&ordinary, "POST", "/api/system/user/addUser"));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::common::config::APP_CONFIG;
+use crate::utils::token_util::{random_token, sha256_hex};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 同一个邮箱两次发送重置密码邮件的最小间隔(秒)
+pub const RESET_MAIL_INTERVAL_SECS: u64 = 60;
+
+lazy_static! {
+    // 重置密码令牌: 令牌哈希 -> (用户ID, 过期时间),只保存哈希值
+    static ref RESET_TOKEN: RwLock<HashMap<String, (i64, u64)>> = RwLock::new(HashMap::new());
+    // 邮箱最近一次申请重置密码的时间
+    static ref RESET_MAIL_SENT: RwLock<HashMap<String, u64>> = RwLock::new(HashMap::new());
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs()
+}
+
+/*
+ *创建重置密码令牌(同一个用户只有最后一次生成的令牌有效)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn create_reset_token(user_id: i64) -> String {
+    let token = random_token();
+    let now = now_secs();
+    let mut tokens = RESET_TOKEN.write().unwrap();
+    tokens.retain(|_, (id, expire)| *id != user_id && *expire > now);
+    tokens.insert(sha256_hex(&token), (user_id, now + APP_CONFIG.password.reset_expire_secs));
+    token
+}
+
+/*
+ *查询重置密码令牌对应的用户ID(已过期返回None)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn get_reset_token(token: &str) -> Option<i64> {
+    RESET_TOKEN
+        .read()
+        .unwrap()
+        .get(&sha256_hex(token))
+        .filter(|(_, expire)| *expire > now_secs())
+        .map(|(user_id, _)| *user_id)
+}
+
+/*
+ *移除重置密码令牌(重置成功后只能使用一次)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn remove_reset_token(token: &str) -> bool {
+    RESET_TOKEN.write().unwrap().remove(&sha256_hex(token)).is_some()
+}
+
+/*
+ *同一个邮箱在发送间隔内只能申请一次重置密码,返回是否允许发送
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn allow_reset_mail(email: &str) -> bool {
+    let now = now_secs();
+    let mut sent = RESET_MAIL_SENT.write().unwrap();
+    sent.retain(|_, x| *x + RESET_MAIL_INTERVAL_SECS > now);
+    if sent.contains_key(&email.to_lowercase()) {
+        return false;
+    }
+    sent.insert(email.to_lowercase(), now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::reset_token_util::{allow_reset_mail, create_reset_token, get_reset_token, remove_reset_token};
+
+    #[test]
+    fn test_reset_token() {
+        let first = create_reset_token(1);
+        let second = create_reset_token(1);
+        assert_eq!(get_reset_token(&first), None);
+        assert_eq!(get_reset_token(&second), Some(1));
+        assert!(remove_reset_token(&second));
+        assert!(!remove_reset_token(&second));
+        assert_eq!(get_reset_token(&second), None);
+    }
+
+    #[test]
+    fn test_allow_reset_mail() {
+        assert!(allow_reset_mail("test@qq.com"));
+        assert!(!allow_reset_mail("Test@qq.com"));
+        assert!(allow_reset_mail("other@qq.com"));
+    }
+}
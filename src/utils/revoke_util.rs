@@ -0,0 +1,75 @@
+use crate::utils::jwt_util::{JwtToken, JWT_CONFIG};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    // 已撤销的访问令牌: jti -> 过期时间(过期后自动清理)
+    static ref REVOKED_TOKEN: RwLock<HashMap<String, usize>> = RwLock::new(HashMap::new());
+    // 用户令牌撤销时间: user_id -> 撤销时间(在此之前及同一秒内签发的令牌全部失效)
+    static ref REVOKED_USER: RwLock<HashMap<i64, usize>> = RwLock::new(HashMap::new());
+    // 已撤销的会话: sid -> 撤销时间(强制退出)
+    static ref REVOKED_SESSION: RwLock<HashMap<String, usize>> = RwLock::new(HashMap::new());
+}
+
+fn now_secs() -> usize {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs() as usize
+}
+
+/*
+ *撤销单个访问令牌(退出登录)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn revoke_token(jti: &str, exp: usize) {
+    let now = now_secs();
+    let mut revoked = REVOKED_TOKEN.write().unwrap();
+    revoked.retain(|_, x| *x > now);
+    revoked.insert(jti.to_string(), exp);
+}
+
+/*
+ *撤销用户所有已签发的访问令牌(禁用、删除用户或修改密码)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn revoke_user(user_id: i64) {
+    let now = now_secs();
+    let max_age = JWT_CONFIG.expire_secs as usize;
+    let mut revoked = REVOKED_USER.write().unwrap();
+    revoked.retain(|_, x| *x + max_age > now);
+    revoked.insert(user_id, now);
+}
+
+/*
+ *撤销会话内所有的访问令牌(强制退出)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn revoke_session(sid: &str) {
+    let now = now_secs();
+    let max_age = JWT_CONFIG.expire_secs as usize;
+    let mut revoked = REVOKED_SESSION.write().unwrap();
+    revoked.retain(|_, x| *x + max_age > now);
+    revoked.insert(sid.to_string(), now);
+}
+
+/*
+ *判断访问令牌是否已撤销
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn is_revoked(token: &JwtToken) -> bool {
+    if REVOKED_TOKEN.read().unwrap().contains_key(&token.jti) {
+        return true;
+    }
+
+    if REVOKED_SESSION.read().unwrap().contains_key(&token.sid) {
+        return true;
+    }
+
+    match REVOKED_USER.read().unwrap().get(&token.id) {
+        None => false,
+        Some(x) => token.iat <= *x,
+    }
+}
@@ -0,0 +1,106 @@
+use crate::utils::user_agent_util::UserAgentUtil;
+use rbatis::rbdc::DateTime;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    // 在线会话: 会话ID(sid) -> 会话信息
+    static ref ONLINE_SESSION: RwLock<HashMap<String, OnlineSession>> = RwLock::new(HashMap::new());
+}
+
+/*
+ *在线会话
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct OnlineSession {
+    pub session_id: String,   //会话ID
+    pub user_id: i64,         //用户ID
+    pub user_name: String,    //用户账号
+    pub ipaddr: String,       //登录IP地址
+    pub browser: String,      //浏览器类型
+    pub os: String,           //操作系统
+    pub login_time: DateTime, //登录时间
+    pub last_seen: DateTime,  //最后访问时间
+    pub expire: usize,        //当前访问令牌过期时间
+}
+
+fn now_secs() -> usize {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs() as usize
+}
+
+/*
+ *登录或刷新令牌时保存会话(刷新时传入原来的登录时间)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn save_session(session_id: &str, user_id: i64, user_name: &str, ipaddr: &str, agent: &UserAgentUtil, login_time: DateTime, expires_in: u64) {
+    let session = OnlineSession {
+        session_id: session_id.to_string(),
+        user_id,
+        user_name: user_name.to_string(),
+        ipaddr: ipaddr.to_string(),
+        browser: agent.browser.clone(),
+        os: agent.os.clone(),
+        login_time,
+        last_seen: DateTime::now(),
+        expire: now_secs() + expires_in as usize,
+    };
+    ONLINE_SESSION.write().unwrap().insert(session_id.to_string(), session);
+}
+
+/*
+ *查询会话的登录时间
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn session_login_time(session_id: &str) -> Option<DateTime> {
+    ONLINE_SESSION.read().unwrap().get(session_id).map(|x| x.login_time.clone())
+}
+
+/*
+ *记录会话访问(JwtAuth权限校验通过后调用,只更新已有的会话)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn touch_session(session_id: &str, ipaddr: &str) {
+    if let Some(session) = ONLINE_SESSION.write().unwrap().get_mut(session_id) {
+        session.ipaddr = ipaddr.to_string();
+        session.last_seen = DateTime::now();
+    }
+}
+
+/*
+ *查询在线会话(访问令牌已过期且未刷新的会话视为离线)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn list_sessions() -> Vec<OnlineSession> {
+    let now = now_secs();
+    let mut sessions = ONLINE_SESSION.write().unwrap();
+    sessions.retain(|_, x| x.expire > now);
+
+    let mut list = sessions.values().cloned().collect::<Vec<OnlineSession>>();
+    list.sort_by_key(|x| -x.login_time.unix_timestamp());
+    list
+}
+
+/*
+ *移除会话
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn remove_session(session_id: &str) -> Option<OnlineSession> {
+    ONLINE_SESSION.write().unwrap().remove(session_id)
+}
+
+/*
+ *移除用户的所有会话
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn remove_user_sessions(user_id: i64) {
+    ONLINE_SESSION.write().unwrap().retain(|_, x| x.user_id != user_id);
+}
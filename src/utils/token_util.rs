@@ -0,0 +1,22 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/*
+ *生成随机令牌(32字节,十六进制)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/*
+ *计算令牌的sha256哈希,数据库只保存哈希值
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn sha256_hex(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,177 @@
+use crate::common::error::{AppError, AppResult};
+use crate::utils::png_util::encode_gray;
+use hmac::{Hmac, Mac};
+use qrcode::{Color, QrCode};
+use rand::RngCore;
+use sha1::Sha1;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// RFC 6238: 时间步长30秒,6位数字,HMAC-SHA1(与主流身份验证器App保持一致)
+const TIME_STEP: u64 = 30;
+const DIGITS: u32 = 6;
+// 允许前后各1个时间步长的时钟偏差
+const ALLOWED_SKEW: i64 = 1;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/*
+ *生成TOTP密钥(20字节,base32编码)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut secret);
+    base32_encode(&secret)
+}
+
+/*
+ *生成otpauth地址(身份验证器App扫码添加)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        url_encode(issuer),
+        url_encode(account),
+        secret,
+        url_encode(issuer),
+        DIGITS,
+        TIME_STEP
+    )
+}
+
+/*
+ *校验验证码,成功返回匹配的时间步(用于防止同一个验证码重复使用)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn verify_code(secret: &str, code: &str) -> Option<u64> {
+    let key = base32_decode(secret)?;
+    let code = code.trim();
+    if code.len() != DIGITS as usize {
+        return None;
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs();
+    let current = (now / TIME_STEP) as i64;
+    (-ALLOWED_SKEW..=ALLOWED_SKEW)
+        .map(|x| current + x)
+        .filter(|x| *x >= 0)
+        .map(|x| x as u64)
+        .find(|x| hotp(&key, *x) == code)
+}
+
+/*
+ *生成二维码图片(png)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn qr_png(content: &str) -> AppResult<Vec<u8>> {
+    const SCALE: usize = 4;
+    const QUIET_ZONE: usize = 4;
+
+    let code = QrCode::new(content.as_bytes()).map_err(|err| {
+        log::error!("create qr code error: {:?}", err);
+        AppError::BusinessError("生成二维码失败")
+    })?;
+
+    let width = code.width();
+    let size = (width + QUIET_ZONE * 2) * SCALE;
+    let mut pixels = vec![255u8; size * size];
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != Color::Dark {
+            continue;
+        }
+        let (x, y) = ((i % width + QUIET_ZONE) * SCALE, (i / width + QUIET_ZONE) * SCALE);
+        for dy in 0..SCALE {
+            pixels[(y + dy) * size + x..(y + dy) * size + x + SCALE].fill(0);
+        }
+    }
+
+    Ok(encode_gray(size as u32, size as u32, &pixels))
+}
+
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC可以使用任意长度的密钥");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0F) as usize;
+    let binary = u32::from_be_bytes([hash[offset] & 0x7F, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+    format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize)
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for &b in data {
+        buffer = (buffer << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            out.push(BASE32_ALPHABET[((buffer >> (bits - 5)) & 0x1F) as usize] as char);
+            bits -= 5;
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in s.trim_end_matches('=').bytes().filter(|x| *x != b' ') {
+        let value = BASE32_ALPHABET.iter().position(|x| *x == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            out.push((buffer >> (bits - 8)) as u8);
+            bits -= 8;
+        }
+    }
+    Some(out)
+}
+
+fn url_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::totp_util::{base32_decode, base32_encode, hotp, otpauth_uri, qr_png, verify_code, TIME_STEP};
+
+    #[test]
+    fn test_rfc6238() {
+        let key = b"12345678901234567890";
+        assert_eq!(hotp(key, 59 / TIME_STEP), "287082");
+        assert_eq!(hotp(key, 1111111109 / TIME_STEP), "081804");
+        assert_eq!(hotp(key, 2000000000 / TIME_STEP), "279037");
+    }
+
+    #[test]
+    fn test_base32() {
+        assert_eq!(base32_encode(b"12345678901234567890"), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), b"12345678901234567890");
+        assert!(base32_decode("1111").is_none());
+    }
+
+    #[test]
+    fn test_verify_code() {
+        assert!(verify_code("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", "000000x").is_none());
+        assert!(verify_code("not base32!", "123456").is_none());
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri("ntex admin", "admin@qq.com", "ABC");
+        assert_eq!(uri, "otpauth://totp/ntex%20admin:admin%40qq.com?secret=ABC&issuer=ntex%20admin&algorithm=SHA1&digits=6&period=30");
+        assert_eq!(&qr_png(&uri).unwrap()[1..4], b"PNG");
+    }
+}
@@ -0,0 +1,86 @@
+use crate::utils::token_util::random_token;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 双因素认证挑战令牌有效期(秒)
+pub const CHALLENGE_EXPIRE_SECS: u64 = 300;
+// 每个挑战令牌允许的最大错误次数
+const MAX_ATTEMPTS: u32 = 5;
+
+lazy_static! {
+    // 双因素认证挑战: 挑战令牌 -> 挑战信息(密码校验通过后生成,验证码校验通过后签发访问令牌)
+    static ref TWO_FACTOR_CHALLENGE: RwLock<HashMap<String, TwoFactorChallenge>> = RwLock::new(HashMap::new());
+}
+
+/*
+ *双因素认证挑战
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+#[derive(Debug, Clone)]
+pub struct TwoFactorChallenge {
+    pub user_id: i64,    //用户ID
+    pub account: String, //登录账号
+    expire: u64,         //过期时间
+    attempts: u32,       //错误次数
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("获取系统时间失败").as_secs()
+}
+
+/*
+ *创建挑战令牌
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn create_challenge(user_id: i64, account: &str) -> String {
+    let token = random_token();
+    let now = now_secs();
+    let mut challenges = TWO_FACTOR_CHALLENGE.write().unwrap();
+    challenges.retain(|_, x| x.expire > now);
+    challenges.insert(
+        token.clone(),
+        TwoFactorChallenge {
+            user_id,
+            account: account.to_string(),
+            expire: now + CHALLENGE_EXPIRE_SECS,
+            attempts: 0,
+        },
+    );
+    token
+}
+
+/*
+ *查询挑战令牌(已过期返回None)
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn get_challenge(token: &str) -> Option<TwoFactorChallenge> {
+    TWO_FACTOR_CHALLENGE.read().unwrap().get(token).filter(|x| x.expire > now_secs()).cloned()
+}
+
+/*
+ *记录一次验证失败,超过最大错误次数后挑战令牌失效
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn fail_challenge(token: &str) {
+    let mut challenges = TWO_FACTOR_CHALLENGE.write().unwrap();
+    if let Some(x) = challenges.get_mut(token) {
+        x.attempts += 1;
+        if x.attempts >= MAX_ATTEMPTS {
+            challenges.remove(token);
+        }
+    }
+}
+
+/*
+ *移除挑战令牌(验证通过后只能使用一次),返回是否存在
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub fn remove_challenge(token: &str) -> bool {
+    TWO_FACTOR_CHALLENGE.write().unwrap().remove(token).is_some()
+}
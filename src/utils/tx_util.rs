@@ -0,0 +1,30 @@
+use crate::common::error::AppResult;
+use crate::RB;
+use futures::future::LocalBoxFuture;
+use rbatis::executor::RBatisTxExecutor;
+
+/*
+ *在事务中执行多条写操作,返回Ok时提交,返回Err时回滚
+ *用法: in_transaction(|tx| Box::pin(async move { User::insert(tx, &user).await?; Ok(()) })).await?
+ *author：刘飞华
+ *date：2025/01/10 09:21:35
+ */
+pub async fn in_transaction<T, F>(f: F) -> AppResult<T>
+where
+    F: for<'a> FnOnce(&'a RBatisTxExecutor) -> LocalBoxFuture<'a, AppResult<T>>,
+{
+    let tx = RB.acquire_begin().await?;
+
+    match f(&tx).await {
+        Ok(x) => {
+            tx.commit().await?;
+            Ok(x)
+        }
+        Err(err) => {
+            if let Err(e) = tx.rollback().await {
+                log::error!("rollback transaction error: {}", e);
+            }
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,25 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use serde::Serialize;
+
+/*
+健康检查响应参数
+*/
+#[derive(Debug, Serialize)]
+pub struct HealthResp {
+    pub status: &'static str, //状态(UP/DOWN)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<&'static str>, //数据库状态(UP/DOWN)
+}
+
+/*
+版本信息响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionResp {
+    pub name: &'static str,     //应用名称
+    pub version: &'static str,  //版本号
+    pub git_hash: &'static str, //git提交哈希
+}
@@ -1 +1 @@
-
+pub mod health_vo;
@@ -0,0 +1,14 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use serde::Serialize;
+
+/*
+图片验证码响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptchaResp {
+    pub captcha_key: String, //验证码key(登录时原样提交)
+    pub image: String,       //验证码图片(data:image/png;base64)
+}
@@ -0,0 +1,61 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use crate::common::result::serialize_datetime;
+use crate::utils::session_util::OnlineSession;
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+
+/*
+查询在线用户列表请求参数
+*/
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryOnlineListReq {
+    pub page_no: u64,
+    pub page_size: u64,
+    pub user_name: Option<String>, //用户账号
+    pub ipaddr: Option<String>,    //登录IP地址
+}
+
+/*
+查询在线用户列表响应参数
+*/
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnlineResp {
+    pub session_id: String, //会话ID
+    pub user_id: i64,       //用户ID
+    pub user_name: String,  //用户账号
+    pub ipaddr: String,     //登录IP地址
+    pub browser: String,    //浏览器类型
+    pub os: String,         //操作系统
+    #[serde(serialize_with = "serialize_datetime")]
+    pub login_time: Option<DateTime>, //登录时间
+    #[serde(serialize_with = "serialize_datetime")]
+    pub last_seen: Option<DateTime>, //最后访问时间
+}
+
+impl From<OnlineSession> for OnlineResp {
+    fn from(item: OnlineSession) -> Self {
+        OnlineResp {
+            session_id: item.session_id,       //会话ID
+            user_id: item.user_id,             //用户ID
+            user_name: item.user_name,         //用户账号
+            ipaddr: item.ipaddr,               //登录IP地址
+            browser: item.browser,             //浏览器类型
+            os: item.os,                       //操作系统
+            login_time: Some(item.login_time), //登录时间
+            last_seen: Some(item.last_seen),   //最后访问时间
+        }
+    }
+}
+
+/*
+强制退出请求参数
+*/
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceLogoutReq {
+    pub session_ids: Vec<String>, //会话ID
+}
@@ -0,0 +1,64 @@
+// author：刘飞华
+// createTime：2025/01/10 09:21:35
+
+use crate::common::result::serialize_datetime;
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+
+/*
+回收站数据类型
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecycleType {
+    User, //用户
+    Role, //角色
+    Dept, //部门
+}
+
+impl RecycleType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecycleType::User => "user",
+            RecycleType::Role => "role",
+            RecycleType::Dept => "dept",
+        }
+    }
+}
+
+/*
+查询回收站列表请求参数
+*/
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRecycleListReq {
+    pub page_no: u64,
+    pub page_size: u64,
+    pub recycle_type: Option<RecycleType>, //数据类型(user:用户,role:角色,dept:部门,为空查询全部)
+    pub name: Option<String>,              //名称
+    pub begin_time: Option<String>,        //删除时间开始(yyyy-MM-dd HH:mm:ss)
+    pub end_time: Option<String>,          //删除时间结束(yyyy-MM-dd HH:mm:ss)
+}
+
+/*
+查询回收站列表响应参数
+*/
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycleResp {
+    pub recycle_type: String, //数据类型(user:用户,role:角色,dept:部门)
+    pub id: i64,              //主键
+    pub name: String,         //名称
+    #[serde(serialize_with = "serialize_datetime")]
+    pub delete_time: Option<DateTime>, //删除时间
+}
+
+/*
+恢复/彻底删除回收站数据请求参数
+*/
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycleReq {
+    pub recycle_type: RecycleType, //数据类型(user:用户,role:角色,dept:部门)
+    pub ids: Vec<i64>,
+}
@@ -119,6 +119,48 @@ pub struct UpdateRoleMenuReq {
     pub role_id: i64,
 }
 
+/*
+查询角色部门信息参数
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRoleDeptReq {
+    pub role_id: i64, //角色id
+}
+
+/*
+角色部门信息参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRoleDeptData {
+    pub dept_ids: Vec<i64>,           //部门Ids
+    pub dept_list: Vec<DeptDataList>, //部门列表
+}
+
+/*
+部门信息参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptDataList {
+    pub id: i64,        //主键
+    pub parent_id: i64, //父ID
+    pub title: String,
+    pub key: String,
+    pub label: String,
+}
+
+/*
+更新角色部门信息
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleDeptReq {
+    pub dept_ids: Vec<i64>,
+    pub role_id: i64,
+}
+
 /*
 查询已分配用户角色列表
 */
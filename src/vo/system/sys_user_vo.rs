@@ -93,15 +93,40 @@ pub struct UserResp {
     pub update_time: Option<DateTime>, //修改时间
     pub dept_info: Option<DeptResp>, //部门详细信息
     pub post_ids: Option<Vec<i64>>, //岗位ids
+    pub lock_status: i8,        //锁定状态(0:正常,1:登录失败次数过多已锁定)
+    pub lock_reason: Option<String>, //锁定原因
 }
 
 /*
 登录请求参数
 */
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct UserLoginReq {
-    pub account: String,  //手机
-    pub password: String, //密码
+    pub account: String,              //手机
+    pub password: String,             //密码
+    pub captcha_key: Option<String>,  //验证码key
+    pub captcha_code: Option<String>, //验证码
+}
+
+/*
+登录响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResp {
+    pub token: String,         //访问令牌
+    pub refresh_token: String, //刷新令牌
+    pub expires_in: u64,       //访问令牌有效期(秒)
+}
+
+/*
+刷新令牌请求参数
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenReq {
+    pub refresh_token: String, //刷新令牌
 }
 
 /*
@@ -160,6 +185,14 @@ pub struct UpdateUserRoleReq {
     pub role_ids: Vec<i64>, //角色主键
 }
 
+/*
+解锁用户请求参数
+*/
+#[derive(Debug, Deserialize)]
+pub struct UnlockUserReq {
+    pub ids: Vec<i64>,
+}
+
 /*
 重置密码
 */
@@ -178,3 +211,102 @@ pub struct UpdateUserPwdReq {
     pub pwd: String,    //用户密码
     pub re_pwd: String, //用户密码
 }
+
+/*
+密码过期修改密码请求参数
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeExpiredPwdReq {
+    pub change_token: String, //密码过期修改令牌
+    pub re_pwd: String,       //新密码
+}
+
+/*
+忘记密码请求参数
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordReq {
+    pub email: String,        //用户邮箱
+    pub captcha_key: String,  //验证码key
+    pub captcha_code: String, //验证码
+}
+
+/*
+通过邮件令牌重置密码请求参数
+*/
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordByTokenReq {
+    pub token: String,    //重置密码令牌
+    pub password: String, //新密码
+}
+
+/*
+登录密码已过期响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordExpiredResp {
+    pub password_expired: bool, //密码已过期,需要修改密码后重新登录
+    pub change_token: String,   //密码过期修改令牌
+    pub expires_in: u64,        //密码过期修改令牌有效期(秒)
+}
+
+/*
+双因素认证密钥响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorSetupResp {
+    pub secret: String,      //TOTP密钥(base32,无法扫码时手动输入)
+    pub otpauth_uri: String, //otpauth地址
+    pub qr_code: String,     //二维码图片(data:image/png;base64)
+}
+
+/*
+开启双因素认证请求参数
+*/
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorEnableReq {
+    pub code: String, //身份验证器App中的验证码
+}
+
+/*
+开启双因素认证响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnableResp {
+    pub recovery_codes: Vec<String>, //恢复码(只显示一次,每个只能使用一次)
+}
+
+/*
+登录需要双因素认证响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorLoginResp {
+    pub two_factor: bool,        //需要双因素认证
+    pub challenge_token: String, //挑战令牌
+    pub expires_in: u64,         //挑战令牌有效期(秒)
+}
+
+/*
+双因素认证请求参数
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorVerifyReq {
+    pub challenge_token: String, //挑战令牌
+    pub code: String,            //验证码或恢复码
+}
+
+/*
+重置双因素认证请求参数
+*/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorResetReq {
+    pub user_ids: Vec<i64>,
+}